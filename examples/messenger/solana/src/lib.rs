@@ -53,9 +53,9 @@ pub enum Instruction {
     SendMessageRaw(Message, u32),
 
     /// This instruction receives a message by processing an incoming VAA containing a message
-    /// intended for a receiver on Solana. Note that the simple existence of the VAA account is
-    /// enough to verify it as the account is only created by the bridge if the guardians had
-    /// successfull signed it.
+    /// intended for a receiver on Solana. The VAA account is only created by the bridge if the
+    /// guardians had successfully signed it, but programs must check they were passed that
+    /// account, see `wormhole_sdk::verify_posted_vaa`.
     ///
     /// 0: VAA [PDA]
     RecvMessage,
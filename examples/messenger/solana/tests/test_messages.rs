@@ -271,8 +271,7 @@ pub async fn simulate_guardians(context: &mut ProgramTestContext, message: &Mess
 
     // Place to store Signatures on Solana.
     let signatures = Keypair::new();
-    let mut signers = [-1; 19];
-    signers[0] = 0;
+    let signers = vec![(0, 0)];
 
     // Verify Signatures
     context
@@ -293,7 +292,10 @@ pub async fn simulate_guardians(context: &mut ProgramTestContext, message: &Mess
                     context.payer.pubkey(),
                     0,
                     signatures.pubkey(),
-                    VerifySignaturesData { signers },
+                    VerifySignaturesData {
+                        signers,
+                        secp_instruction_index: None,
+                    },
                 ).unwrap(),
             ],
             Some(&context.payer.pubkey()),
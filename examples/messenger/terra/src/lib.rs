@@ -41,6 +41,7 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
         ExecuteMsg::SendMessage { nonce, nick, text } => Ok(Response::default()
             .add_attribute("action", "send_message")
             .add_message(post_message(
+                &deps.querier,
                 nonce,
                 &Message { nick, text }
                     .try_to_vec()
@@ -69,12 +70,20 @@ mod testing {
         mock_dependencies,
         mock_env,
         mock_info,
+        MockApi,
+        MockStorage,
     };
     use cosmwasm_std::{
         Attribute,
         Binary,
+        Coin,
+        ContractResult,
         CosmosMsg,
+        OwnedDeps,
+        Querier,
+        QuerierResult,
         SubMsg,
+        SystemResult,
         WasmMsg,
     };
     use messenger_common::Message;
@@ -99,8 +108,21 @@ mod testing {
             text:  "Hello Alice".to_string(),
         };
 
+        // The Wormhole contract charges a fee for posting messages, which the SDK queries.
+        struct WormholeQuerier;
+        impl Querier for WormholeQuerier {
+            fn raw_query(&self, _: &[u8]) -> QuerierResult {
+                let state = br#"{"fee":{"denom":"uluna","amount":"10000"}}"#;
+                SystemResult::Ok(ContractResult::Ok(Binary::from(&state[..])))
+            }
+        }
+
         // Instantiate Contract
-        let mut deps = mock_dependencies(&[]);
+        let mut deps = OwnedDeps {
+            storage: MockStorage::default(),
+            api:     MockApi::default(),
+            querier: WormholeQuerier,
+        };
         let env = mock_env();
         let info = mock_info("addr0000", &[]);
         instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -114,7 +136,7 @@ mod testing {
             result.messages,
             vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
                 contract_addr: wormhole_sdk::id().to_string(),
-                funds:         vec![],
+                funds:         vec![Coin::new(10000, "uluna")],
                 msg:           Binary::from(&[
                     123, 34, 112, 111, 115, 116, 95, 109, 101, 115, 115, 97, 103, 101, 34, 58, 123,
                     34, 109, 101, 115, 115, 97, 103, 101, 34, 58, 34, 87, 122, 77, 115, 77, 67,
@@ -2,6 +2,7 @@ package solana
 
 import (
 	"context"
+	"encoding/base64"
 	"errors"
 	"fmt"
 	"github.com/certusone/wormhole/node/pkg/common"
@@ -19,6 +20,7 @@ import (
 	"github.com/prometheus/client_golang/prometheus"
 	"github.com/prometheus/client_golang/prometheus/promauto"
 	"go.uber.org/zap"
+	"strings"
 	"time"
 )
 
@@ -86,10 +88,41 @@ func (c ConsistencyLevel) Commitment() (rpc.CommitmentType, error) {
 }
 
 const (
-	postMessageInstructionNumAccounts = 9
-	postMessageInstructionID          = 0x01
+	// Emitters governance has set a fee for append their EmitterFee account to the nine accounts
+	// every message publication carries.
+	postMessageInstructionMinNumAccounts = 9
+	postMessageInstructionID             = 0x01
+	postMessageUnreliableInstructionID   = 0x0b
 )
 
+const (
+	// Events are logged with sol_log_data as the event version followed by the borsh encoded
+	// event, which the runtime renders as a "Program data: <base64>" log line.
+	programDataLogPrefix = "Program data: "
+	eventVersion         = 1
+	eventMessagePosted   = 0
+)
+
+// isPostMessageInstruction reports whether the instruction data belongs to one of the
+// instructions that publish a message account: post_message or post_message_unreliable.
+func isPostMessageInstruction(data []byte) bool {
+	if len(data) == 0 {
+		return false
+	}
+	return data[0] == postMessageInstructionID || data[0] == postMessageUnreliableInstructionID
+}
+
+// isMessageAccount reports whether the account data carries the prefix of a posted message:
+// b"msg" for post_message, or b"msu" for post_message_unreliable, whose account is overwritten
+// by every message the emitter posts and is zero-padded when a shorter message follows a longer one.
+func isMessageAccount(data []byte) bool {
+	if len(data) < 3 {
+		return false
+	}
+	prefix := string(data[:3])
+	return prefix == "msg" || prefix == "msu"
+}
+
 // PostMessageData represents the user-supplied, untrusted instruction data
 // for message publications. We use this to determine consistency level before fetching accounts.
 type PostMessageData struct {
@@ -312,9 +345,11 @@ OUTER:
 			zap.Uint64("slot", slot),
 			zap.String("commitment", string(s.commitment)))
 
+		posted := &postedMessages{contract: s.contract, logs: tx.Meta.LogMessages}
+
 		// Find top-level instructions
 		for i, inst := range tx.Transaction.Message.Instructions {
-			found, err := s.processInstruction(ctx, logger, slot, inst, programIndex, tx, signature, i)
+			found, err := s.processInstruction(ctx, logger, slot, inst, programIndex, tx, posted, signature, i)
 			if err != nil {
 				logger.Error("malformed Wormhole instruction",
 					zap.Error(err),
@@ -358,7 +393,7 @@ OUTER:
 
 		for _, inner := range tr.Meta.InnerInstructions {
 			for i, inst := range inner.Instructions {
-				_, err := s.processInstruction(ctx, logger, slot, inst, programIndex, tx, signature, i)
+				_, err := s.processInstruction(ctx, logger, slot, inst, programIndex, tx, posted, signature, i)
 				if err != nil {
 					logger.Error("malformed Wormhole instruction",
 						zap.Error(err),
@@ -381,18 +416,18 @@ OUTER:
 	return true
 }
 
-func (s *SolanaWatcher) processInstruction(ctx context.Context, logger *zap.Logger, slot uint64, inst solana.CompiledInstruction, programIndex uint16, tx rpc.TransactionWithMeta, signature solana.Signature, idx int) (bool, error) {
+func (s *SolanaWatcher) processInstruction(ctx context.Context, logger *zap.Logger, slot uint64, inst solana.CompiledInstruction, programIndex uint16, tx rpc.TransactionWithMeta, posted *postedMessages, signature solana.Signature, idx int) (bool, error) {
 	if inst.ProgramIDIndex != programIndex {
 		return false, nil
 	}
 
-	if inst.Data[0] != postMessageInstructionID {
+	if !isPostMessageInstruction(inst.Data) {
 		return false, nil
 	}
 
-	if len(inst.Accounts) != postMessageInstructionNumAccounts {
-		return false, fmt.Errorf("invalid number of accounts: %d instead of %d",
-			len(inst.Accounts), postMessageInstructionNumAccounts)
+	if len(inst.Accounts) < postMessageInstructionMinNumAccounts {
+		return false, fmt.Errorf("invalid number of accounts: %d instead of at least %d",
+			len(inst.Accounts), postMessageInstructionMinNumAccounts)
 	}
 
 	// Decode instruction data (UNTRUSTED)
@@ -404,6 +439,20 @@ func (s *SolanaWatcher) processInstruction(ctx context.Context, logger *zap.Logg
 	logger.Info("post message data", zap.Any("deserialized_data", data),
 		zap.Stringer("signature", signature), zap.Uint64("slot", slot), zap.Int("idx", idx))
 
+	// The second account in a well-formed Wormhole instruction is the VAA program account.
+	acc := tx.Transaction.Message.AccountKeys[inst.Accounts[1]]
+
+	// Unreliable messages are written to an account the emitter's next message overwrites, so
+	// they are taken from the event logged when posting rather than fetched. The event is taken
+	// before checking the commitment to keep events matched to their instructions.
+	var event *MessagePostedEvent
+	if inst.Data[0] == postMessageUnreliableInstructionID {
+		var err error
+		if event, err = posted.next(acc); err != nil {
+			return false, fmt.Errorf("failed to find unreliable message: %w", err)
+		}
+	}
+
 	level, err := data.ConsistencyLevel.Commitment()
 	if err != nil {
 		return false, fmt.Errorf("failed to determine commitment: %w", err)
@@ -413,8 +462,13 @@ func (s *SolanaWatcher) processInstruction(ctx context.Context, logger *zap.Logg
 		return true, nil
 	}
 
-	// The second account in a well-formed Wormhole instruction is the VAA program account.
-	acc := tx.Transaction.Message.AccountKeys[inst.Accounts[1]]
+	if event != nil {
+		logger.Info("found unreliable message event", zap.Stringer("acc", acc),
+			zap.Stringer("signature", signature), zap.Uint64("slot", slot), zap.Int("idx", idx))
+
+		s.publishMessage(logger, event.publicationAccount(), acc)
+		return true, nil
+	}
 
 	logger.Info("fetching VAA account", zap.Stringer("acc", acc),
 		zap.Stringer("signature", signature), zap.Uint64("slot", slot), zap.Int("idx", idx))
@@ -482,7 +536,7 @@ func (s *SolanaWatcher) fetchMessageAccount(ctx context.Context, logger *zap.Log
 	}
 
 	data := info.Value.Data.GetBinary()
-	if string(data[:3]) != "msg" {
+	if !isMessageAccount(data) {
 		p2p.DefaultRegistry.AddErrorCount(vaa.ChainIDSolana, 1)
 		solanaConnectionErrors.WithLabelValues(string(s.commitment), "bad_account_data").Inc()
 		logger.Error("account is not a message account",
@@ -514,6 +568,10 @@ func (s *SolanaWatcher) processMessageAccount(logger *zap.Logger, data []byte, a
 		return
 	}
 
+	s.publishMessage(logger, proposal, acc)
+}
+
+func (s *SolanaWatcher) publishMessage(logger *zap.Logger, proposal *MessagePublicationAccount, acc solana.PublicKey) {
 	var txHash eth_common.Hash
 	copy(txHash[:], acc[:])
 
@@ -558,14 +616,136 @@ type (
 		EmitterAddress      vaa.Address
 		Payload             []byte
 	}
+
+	// MessagePostedEvent is the event the bridge logs for every message it writes, carrying the
+	// message so that it can be observed after its account has been reused.
+	MessagePostedEvent struct {
+		Message          solana.PublicKey
+		EmitterAddress   vaa.Address
+		Sequence         uint64
+		Nonce            uint32
+		ConsistencyLevel uint8
+		SubmissionTime   uint32
+		Payload          []byte
+	}
 )
 
 func ParseMessagePublicationAccount(data []byte) (*MessagePublicationAccount, error) {
 	prop := &MessagePublicationAccount{}
-	// Skip the b"msg" prefix
+	// Skip the b"msg" or b"msu" prefix
 	if err := borsh.Deserialize(prop, data[3:]); err != nil {
 		return nil, err
 	}
 
 	return prop, nil
 }
+
+// publicationAccount returns the message as it was written to its account.
+func (e *MessagePostedEvent) publicationAccount() *MessagePublicationAccount {
+	return &MessagePublicationAccount{
+		ConsistencyLevel: e.ConsistencyLevel,
+		SubmissionTime:   e.SubmissionTime,
+		Nonce:            e.Nonce,
+		Sequence:         e.Sequence,
+		EmitterChain:     uint16(vaa.ChainIDSolana),
+		EmitterAddress:   e.EmitterAddress,
+		Payload:          e.Payload,
+	}
+}
+
+// ParseMessagePostedEvents decodes the MessagePosted events logged by the bridge at contract from
+// the log messages of a transaction, in the order the messages were posted. Invocations are
+// tracked so that data logged by other programs, including programs invoked by the bridge or
+// programs invoking it, is skipped.
+func ParseMessagePostedEvents(contract solana.PublicKey, logs []string) ([]*MessagePostedEvent, error) {
+	program := contract.String()
+	var invocations []string
+	var events []*MessagePostedEvent
+
+	for _, log := range logs {
+		if strings.HasPrefix(log, programDataLogPrefix) {
+			if len(invocations) == 0 || invocations[len(invocations)-1] != program {
+				continue
+			}
+			event, err := parseMessagePostedEvent(strings.TrimPrefix(log, programDataLogPrefix))
+			if err != nil {
+				return nil, err
+			}
+			if event != nil {
+				events = append(events, event)
+			}
+			continue
+		}
+
+		// Invocations are logged as "Program <id> invoke [<depth>]" and end with either
+		// "Program <id> success" or "Program <id> failed: <error>".
+		words := strings.Split(log, " ")
+		if len(words) < 3 || words[0] != "Program" {
+			continue
+		}
+		switch words[2] {
+		case "invoke":
+			invocations = append(invocations, words[1])
+		case "success", "failed:":
+			if len(invocations) > 0 {
+				invocations = invocations[:len(invocations)-1]
+			}
+		}
+	}
+
+	return events, nil
+}
+
+// parseMessagePostedEvent decodes the data of a "Program data:" log line, returning nil for
+// events other than MessagePosted.
+func parseMessagePostedEvent(data string) (*MessagePostedEvent, error) {
+	raw, err := base64.StdEncoding.DecodeString(data)
+	if err != nil {
+		return nil, fmt.Errorf("invalid base64 in event log: %w", err)
+	}
+	if len(raw) < 2 {
+		return nil, errors.New("event log too short")
+	}
+	if raw[0] != eventVersion {
+		return nil, fmt.Errorf("unsupported event version %d", raw[0])
+	}
+	if raw[1] != eventMessagePosted {
+		return nil, nil
+	}
+
+	event := &MessagePostedEvent{}
+	if err := borsh.Deserialize(event, raw[2:]); err != nil {
+		return nil, fmt.Errorf("failed to deserialize MessagePosted event: %w", err)
+	}
+
+	return event, nil
+}
+
+// postedMessages hands the MessagePosted events logged by a transaction to the unreliable message
+// instructions that logged them. Instructions are processed in the order they were executed, so
+// the first event not yet taken for an account belongs to the next instruction posting to it.
+type postedMessages struct {
+	contract solana.PublicKey
+	logs     []string
+	events   []*MessagePostedEvent
+	decoded  bool
+}
+
+func (p *postedMessages) next(acc solana.PublicKey) (*MessagePostedEvent, error) {
+	if !p.decoded {
+		events, err := ParseMessagePostedEvents(p.contract, p.logs)
+		if err != nil {
+			return nil, err
+		}
+		p.events, p.decoded = events, true
+	}
+
+	for i, event := range p.events {
+		if event.Message.Equals(acc) {
+			p.events = append(p.events[:i:i], p.events[i+1:]...)
+			return event, nil
+		}
+	}
+
+	return nil, fmt.Errorf("no MessagePosted event logged for %s", acc)
+}
@@ -0,0 +1,163 @@
+package solana
+
+import (
+	"bytes"
+	"encoding/base64"
+	"testing"
+
+	"github.com/certusone/wormhole/node/pkg/common"
+	"github.com/certusone/wormhole/node/pkg/vaa"
+	"github.com/gagliardetto/solana-go"
+	"github.com/near/borsh-go"
+	"go.uber.org/zap"
+)
+
+func TestIsPostMessageInstruction(t *testing.T) {
+	tests := []struct {
+		name string
+		data []byte
+		want bool
+	}{
+		{"post_message", []byte{postMessageInstructionID, 0x00}, true},
+		{"post_message_unreliable", []byte{postMessageUnreliableInstructionID, 0x00}, true},
+		{"post_vaa", []byte{0x02, 0x00}, false},
+		{"empty", []byte{}, false},
+	}
+
+	for _, tc := range tests {
+		t.Run(tc.name, func(t *testing.T) {
+			if got := isPostMessageInstruction(tc.data); got != tc.want {
+				t.Fatalf("expected %v got %v", tc.want, got)
+			}
+		})
+	}
+}
+
+func TestIsMessageAccount(t *testing.T) {
+	tests := []struct {
+		name string
+		data []byte
+		want bool
+	}{
+		{"message", []byte("msg\x00"), true},
+		{"unreliable message", []byte("msu\x00"), true},
+		{"posted vaa", []byte("vaa\x00"), false},
+		{"short", []byte("ms"), false},
+	}
+
+	for _, tc := range tests {
+		t.Run(tc.name, func(t *testing.T) {
+			if got := isMessageAccount(tc.data); got != tc.want {
+				t.Fatalf("expected %v got %v", tc.want, got)
+			}
+		})
+	}
+}
+
+func TestParseUnreliableMessageAccount(t *testing.T) {
+	want := MessagePublicationAccount{
+		VaaVersion:       1,
+		ConsistencyLevel: 1,
+		SubmissionTime:   1640000000,
+		Nonce:            42,
+		Sequence:         7,
+		EmitterChain:     uint16(vaa.ChainIDSolana),
+		EmitterAddress:   vaa.Address{1, 2, 3},
+		Payload:          []byte("hello"),
+	}
+
+	body, err := borsh.Serialize(want)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	// The account keeps the size of a longer message posted earlier by the same emitter.
+	data := append([]byte("msu"), body...)
+	data = append(data, make([]byte, 16)...)
+
+	if !isMessageAccount(data) {
+		t.Fatal("expected unreliable message account to be accepted")
+	}
+
+	got, err := ParseMessagePublicationAccount(data)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	if got.Nonce != want.Nonce || got.Sequence != want.Sequence ||
+		got.EmitterChain != want.EmitterChain || got.EmitterAddress != want.EmitterAddress ||
+		got.ConsistencyLevel != want.ConsistencyLevel || !bytes.Equal(got.Payload, want.Payload) {
+		t.Fatalf("expected %+v got %+v", want, *got)
+	}
+}
+
+func messagePostedLog(t *testing.T, event MessagePostedEvent) string {
+	body, err := borsh.Serialize(event)
+	if err != nil {
+		t.Fatal(err)
+	}
+	data := append([]byte{eventVersion, eventMessagePosted}, body...)
+	return programDataLogPrefix + base64.StdEncoding.EncodeToString(data)
+}
+
+func TestUnreliableMessagesFromEvents(t *testing.T) {
+	contract := solana.PublicKey{1}
+	other := solana.PublicKey{2}
+	acc := solana.PublicKey{3}
+
+	// Two back-to-back posts into the same unreliable account, the second overwriting the first.
+	first := MessagePostedEvent{
+		Message:          acc,
+		EmitterAddress:   vaa.Address{4},
+		Sequence:         7,
+		Nonce:            1,
+		ConsistencyLevel: 1,
+		SubmissionTime:   1640000000,
+		Payload:          []byte("hello"),
+	}
+	second := first
+	second.Sequence = 8
+	second.Nonce = 2
+	second.Payload = []byte("hi")
+
+	// Data logged by another program is skipped, even when the bridge invoked it.
+	forged := first
+	forged.Sequence = 100
+
+	logs := []string{
+		"Program " + contract.String() + " invoke [1]",
+		messagePostedLog(t, first),
+		"Program " + other.String() + " invoke [2]",
+		messagePostedLog(t, forged),
+		"Program " + other.String() + " success",
+		"Program " + contract.String() + " success",
+		"Program " + other.String() + " invoke [1]",
+		"Program " + contract.String() + " invoke [2]",
+		messagePostedLog(t, second),
+		"Program " + contract.String() + " success",
+		"Program " + other.String() + " success",
+	}
+
+	s := &SolanaWatcher{contract: contract, messageEvent: make(chan *common.MessagePublication, 2)}
+	posted := &postedMessages{contract: contract, logs: logs}
+	for i := 0; i < 2; i++ {
+		event, err := posted.next(acc)
+		if err != nil {
+			t.Fatal(err)
+		}
+		s.publishMessage(zap.NewNop(), event.publicationAccount(), acc)
+	}
+
+	if _, err := posted.next(acc); err == nil {
+		t.Fatal("expected no event to be left for the account")
+	}
+
+	for _, want := range []MessagePostedEvent{first, second} {
+		got := <-s.messageEvent
+		if got.Sequence != want.Sequence || got.Nonce != want.Nonce ||
+			got.EmitterChain != vaa.ChainIDSolana || got.EmitterAddress != want.EmitterAddress ||
+			got.ConsistencyLevel != want.ConsistencyLevel || !bytes.Equal(got.Payload, want.Payload) {
+			t.Fatalf("expected %+v got %+v", want, *got)
+		}
+	}
+}
@@ -0,0 +1,266 @@
+//! Command line tool for inspecting Wormhole VAAs. Every command goes through the wormhole-core
+//! parsers, so its output always matches what the library accepts.
+//!
+//! VAAs can be given as hex, optionally prefixed with `0x`, or as base64. Passing `-` instead of a
+//! VAA reads it from stdin.
+
+use std::error::Error;
+use std::io::Read;
+
+use serde_json::Value;
+use wormhole_core::{
+    Chain,
+    ForeignAddress,
+    GuardianAddress,
+    Module,
+    Payload,
+    VAA,
+};
+
+const USAGE: &str = "\
+Usage: wormhole <command> [options] <input>
+
+Commands:
+  decode [--emitter <chain>:<address>:<module>]... <vaa>
+      Print the VAA and its classified payload as JSON. Payloads are classified by their emitter,
+      given as a chain name or ID, a 32 byte hex address, and one of core, tokenbridge or
+      nftbridge. Governance payloads are recognised from any emitter.
+
+  digest <vaa>
+      Print the hash of the VAA body, which identifies the VAA across all Wormhole contracts.
+
+  verify --guardians <address>[,<address>]... <vaa>
+      Verify the VAA signatures against the given guardian set, as the contracts do.
+
+  encode <json>
+      Encode a VAA from JSON, in the format printed by decode, and print it as hex.";
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => println!("{}", output),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<String> {
+    let (command, args) = args.split_first().ok_or(USAGE)?;
+    let (options, input) = match args.split_last() {
+        Some((input, options)) => (options, input.as_str()),
+        None => return Err(USAGE.into()),
+    };
+
+    match command.as_str() {
+        "decode" => {
+            let emitters = options_for(options, "--emitter")?
+                .into_iter()
+                .map(parse_emitter)
+                .collect::<Result<Vec<_>>>()?;
+            decode(&read_input(input)?, &emitters)
+        }
+
+        "digest" => {
+            no_options(options)?;
+            let vaa = VAA::from_bytes(decode_bytes(&read_input(input)?)?)?;
+            Ok(hex::encode(digest(&vaa)?))
+        }
+
+        "verify" => {
+            let guardians = match options_for(options, "--guardians")?.as_slice() {
+                [guardians] => parse_guardians(guardians)?,
+                _ => return Err("verify requires a single --guardians option".into()),
+            };
+            let vaa = VAA::from_bytes(decode_bytes(&read_input(input)?)?)?;
+            vaa.verify(&guardians)?;
+            Ok("ok".into())
+        }
+
+        "encode" => {
+            no_options(options)?;
+            encode(&read_input(input)?)
+        }
+
+        _ => Err(USAGE.into()),
+    }
+}
+
+/// Decode a VAA to JSON. The VAA fields are printed in the same format `encode` accepts, followed
+/// by the body, its hash, and the classified payload.
+fn decode(input: &str, emitters: &[(Chain, ForeignAddress, Module)]) -> Result<String> {
+    let vaa = VAA::from_bytes(decode_bytes(input)?)?;
+    let digest = vaa.digest().ok_or("failed to hash VAA body")?;
+
+    let mut output = serde_json::to_value(&vaa)?;
+    output["body"] = Value::String(hex::encode(&digest.digest));
+    output["hash"] = Value::String(hex::encode(digest.hash));
+    match Payload::classify(&vaa, emitters) {
+        Ok(payload) => output["parsed"] = serde_json::to_value(payload)?,
+        Err(e) => output["parse_error"] = Value::String(e.to_string()),
+    }
+
+    Ok(serde_json::to_string_pretty(&output)?)
+}
+
+/// Encode a VAA from JSON. Fields added by `decode` that are derived from the VAA are ignored.
+fn encode(input: &str) -> Result<String> {
+    let vaa: VAA = serde_json::from_str(input)?;
+    Ok(hex::encode(vaa.to_bytes()))
+}
+
+fn digest(vaa: &VAA) -> Result<[u8; 32]> {
+    Ok(vaa.digest().ok_or("failed to hash VAA body")?.hash)
+}
+
+/// Read the input argument, or stdin if it is `-`.
+fn read_input(input: &str) -> Result<String> {
+    if input != "-" {
+        return Ok(input.to_string());
+    }
+
+    let mut buffer = String::new();
+    std::io::stdin().read_to_string(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Decode hex, optionally prefixed with `0x`, falling back to base64.
+fn decode_bytes(input: &str) -> Result<Vec<u8>> {
+    let input = input.trim();
+    let hex = input.strip_prefix("0x").unwrap_or(input);
+    if let Ok(bytes) = hex::decode(hex) {
+        return Ok(bytes);
+    }
+
+    base64::decode(input).map_err(|_| "VAA is neither hex nor base64".into())
+}
+
+/// Collect the values of an option that may be repeated, rejecting any unknown options.
+fn options_for<'a>(options: &'a [String], name: &str) -> Result<Vec<&'a str>> {
+    let mut values = Vec::new();
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = options.next().ok_or_else(|| format!("{} requires a value", option))?;
+        if option == name {
+            values.push(value.as_str());
+        } else if !matches!(option.as_str(), "--emitter" | "--guardians") {
+            return Err(format!("unknown option {}", option).into());
+        }
+    }
+    Ok(values)
+}
+
+fn no_options(options: &[String]) -> Result<()> {
+    match options.first() {
+        Some(option) => Err(format!("unexpected option {}", option).into()),
+        None => Ok(()),
+    }
+}
+
+/// Parse an emitter given as `<chain>:<address>:<module>`.
+fn parse_emitter(emitter: &str) -> Result<(Chain, ForeignAddress, Module)> {
+    let parts: Vec<&str> = emitter.split(':').collect();
+    let (chain, address, module) = match parts.as_slice() {
+        [chain, address, module] => (chain, address, module),
+        _ => return Err(format!("expected <chain>:<address>:<module>, got {}", emitter).into()),
+    };
+
+    let module = match module.to_lowercase().as_str() {
+        "core" => Module::Core,
+        "tokenbridge" => Module::TokenBridge,
+        "nftbridge" => Module::NFTBridge,
+        _ => return Err(format!("unknown module {}", module).into()),
+    };
+
+    Ok((chain.parse()?, parse_fixed(address)?, module))
+}
+
+/// Parse a comma separated list of hex guardian addresses.
+fn parse_guardians(guardians: &str) -> Result<Vec<GuardianAddress>> {
+    guardians.split(',').map(parse_fixed).collect()
+}
+
+fn parse_fixed<const N: usize>(input: &str) -> Result<[u8; N]> {
+    let input = input.strip_prefix("0x").unwrap_or(input);
+    let mut output = [0u8; N];
+    hex::decode_to_slice(input, &mut output)
+        .map_err(|_| format!("expected {} hex encoded bytes, got {}", N, input))?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use wormhole_core::{
+        guardian_address,
+        SecretKey,
+        VAABuilder,
+    };
+
+    fn run(args: &[&str]) -> Result<String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        super::run(&args)
+    }
+
+    fn guardian() -> SecretKey {
+        SecretKey::parse(&[1u8; 32]).unwrap()
+    }
+
+    fn signed_vaa() -> VAA {
+        VAABuilder::new()
+            .timestamp(100)
+            .emitter_chain(Chain::Ethereum)
+            .emitter_address([1u8; 32])
+            .sequence(7)
+            .payload(vec![1, 2, 3])
+            .sign(&[guardian()])
+    }
+
+    #[test]
+    fn test_decode_encode_round_trip() {
+        let vaa = signed_vaa();
+        let bytes = hex::encode(vaa.to_bytes());
+
+        // Hex and base64 input decode to the same output.
+        let decoded = run(&["decode", &bytes]).unwrap();
+        assert_eq!(decoded, run(&["decode", &base64::encode(vaa.to_bytes())]).unwrap());
+        assert_eq!(decoded, run(&["decode", &format!("0x{}", bytes)]).unwrap());
+
+        let json: Value = serde_json::from_str(&decoded).unwrap();
+        assert_eq!(json["sequence"], 7);
+        assert_eq!(json["hash"], hex::encode(vaa.digest().unwrap().hash));
+        assert_eq!(json["parsed"], "Unknown");
+
+        // Decoded output encodes back to the original VAA.
+        assert_eq!(run(&["encode", &decoded]).unwrap(), bytes);
+        assert_eq!(run(&["digest", &bytes]).unwrap(), json["hash"]);
+    }
+
+    #[test]
+    fn test_decode_emitters() {
+        let bytes = hex::encode(signed_vaa().to_bytes());
+        let emitter = format!("ethereum:{}:tokenbridge", hex::encode([1u8; 32]));
+
+        // The payload is too short for a token transfer, so classifying it fails.
+        let decoded = run(&["decode", "--emitter", &emitter, &bytes]).unwrap();
+        let json: Value = serde_json::from_str(&decoded).unwrap();
+        assert_eq!(json["parse_error"], "input ended at byte 3 while reading amount");
+
+        let unknown_module = emitter.replace("token", "fungible");
+        assert!(run(&["decode", "--emitter", "ethereum:01:tokenbridge", &bytes]).is_err());
+        assert!(run(&["decode", "--emitter", &unknown_module, &bytes]).is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        let bytes = hex::encode(signed_vaa().to_bytes());
+        let guardian = hex::encode(guardian_address(&guardian()));
+
+        assert_eq!(run(&["verify", "--guardians", &guardian, &bytes]).unwrap(), "ok");
+        assert!(run(&["verify", "--guardians", &hex::encode([2u8; 20]), &bytes]).is_err());
+        assert!(run(&["verify", &bytes]).is_err());
+    }
+}
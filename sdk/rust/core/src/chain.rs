@@ -1,36 +1,64 @@
 //! Exposes an API implementation depending on which feature flags have been toggled for the
 //! library. Check submodules for chain runtime specific documentation.
-use std::convert::TryFrom; // Remove in 2021
+use core::fmt;
+use core::str::FromStr;
+
+use crate::WormholeError;
 
 
 /// Chain contains a mapping of Wormhole supported chains to their u16 representation. These are
 /// universally defined among all Wormhole contracts.
-#[repr(u16)]
-#[derive(Clone, Debug, PartialEq)]
+///
+/// The set of chains grows as Wormhole is deployed to new networks, so any ID this library does not
+/// yet know about is preserved as `Unknown` rather than rejected. Always convert from raw IDs with
+/// `Chain::from`, which guarantees known IDs never end up wrapped in `Unknown`.
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "u16", into = "u16")
+)]
 pub enum Chain {
-    All      = 0,
-    Solana   = 1,
-    Ethereum = 2,
-    Terra    = 3,
-    Binance  = 4,
-    Polygon  = 5,
-    AVAX     = 6,
-    Oasis    = 7,
-}
-
-impl TryFrom<u16> for Chain {
-    type Error = ();
-    fn try_from(other: u16) -> Result<Chain, Self::Error> {
+    All,
+    Solana,
+    Ethereum,
+    Terra,
+    Binance,
+    Polygon,
+    AVAX,
+    Oasis,
+    Unknown(u16),
+}
+
+impl From<u16> for Chain {
+    fn from(other: u16) -> Chain {
+        match other {
+            0 => Chain::All,
+            1 => Chain::Solana,
+            2 => Chain::Ethereum,
+            3 => Chain::Terra,
+            4 => Chain::Binance,
+            5 => Chain::Polygon,
+            6 => Chain::AVAX,
+            7 => Chain::Oasis,
+            c => Chain::Unknown(c),
+        }
+    }
+}
+
+impl From<Chain> for u16 {
+    fn from(other: Chain) -> u16 {
         match other {
-            0 => Ok(Chain::All),
-            1 => Ok(Chain::Solana),
-            2 => Ok(Chain::Ethereum),
-            3 => Ok(Chain::Terra),
-            4 => Ok(Chain::Binance),
-            5 => Ok(Chain::Polygon),
-            6 => Ok(Chain::AVAX),
-            7 => Ok(Chain::Oasis),
-            _ => Err(()),
+            Chain::All => 0,
+            Chain::Solana => 1,
+            Chain::Ethereum => 2,
+            Chain::Terra => 3,
+            Chain::Binance => 4,
+            Chain::Polygon => 5,
+            Chain::AVAX => 6,
+            Chain::Oasis => 7,
+            Chain::Unknown(c) => c,
         }
     }
 }
@@ -40,3 +68,79 @@ impl Default for Chain {
         Self::All
     }
 }
+
+/// Chains are borsh encoded as their u16 ID, the same as on the wire.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Chain {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        u16::from(*self).serialize(writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Chain {
+    fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+        Ok(Chain::from(u16::deserialize(buf)?))
+    }
+}
+
+/// Chains are displayed using the same names as the guardian node, unknown chains are displayed
+/// as their numeric ID so that the output can always be parsed back with `FromStr`.
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Chain::All => f.write_str("all"),
+            Chain::Solana => f.write_str("solana"),
+            Chain::Ethereum => f.write_str("ethereum"),
+            Chain::Terra => f.write_str("terra"),
+            Chain::Binance => f.write_str("bsc"),
+            Chain::Polygon => f.write_str("polygon"),
+            Chain::AVAX => f.write_str("avalanche"),
+            Chain::Oasis => f.write_str("oasis"),
+            Chain::Unknown(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+/// Parses either a chain name, case insensitively, or a numeric chain ID.
+impl FromStr for Chain {
+    type Err = WormholeError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "all" => Ok(Chain::All),
+            "solana" => Ok(Chain::Solana),
+            "ethereum" => Ok(Chain::Ethereum),
+            "terra" => Ok(Chain::Terra),
+            "bsc" => Ok(Chain::Binance),
+            "polygon" => Ok(Chain::Polygon),
+            "avalanche" => Ok(Chain::AVAX),
+            "oasis" => Ok(Chain::Oasis),
+            s => s.parse::<u16>().map(Chain::from).map_err(|_| WormholeError::InvalidChain),
+        }
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::Chain;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_chain_conversions() {
+        // Every ID survives a round trip, known IDs are never wrapped in Unknown.
+        for id in 0..=u16::MAX {
+            let chain = Chain::from(id);
+            assert_eq!(u16::from(chain), id);
+            assert_eq!(chain.to_string().parse::<Chain>().unwrap(), chain);
+        }
+
+        assert_eq!(Chain::from(3), Chain::Terra);
+        assert_eq!(Chain::from(8), Chain::Unknown(8));
+        assert_eq!("BSC".parse::<Chain>().unwrap(), Chain::Binance);
+        assert_eq!("7".parse::<Chain>().unwrap(), Chain::Oasis);
+        assert!("mars".parse::<Chain>().is_err());
+    }
+}
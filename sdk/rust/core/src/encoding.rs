@@ -0,0 +1,198 @@
+//! Helpers for the optional `serde` and `borsh` encodings of Wormhole types.
+//!
+//! With `serde`, byte fields such as addresses and signatures are encoded as hex strings and U256
+//! values as decimal strings, so the types can be returned directly in JSON responses. The serde
+//! modules here can be reused with `#[serde(with = "...")]` by types that embed Wormhole fields.
+//!
+//! With `borsh`, types use their natural borsh layout. U256 values, which have no borsh support
+//! of their own, are written as 32 little-endian bytes.
+
+/// Encode any byte container, such as `[u8; 32]` or `Vec<u8>`, as a hex string.
+#[cfg(feature = "serde")]
+pub mod hex_bytes {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::convert::TryFrom; // Remove in 2021
+    use serde::de::Error;
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serializer,
+    };
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(value: &T, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&hex::encode(value))
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<Vec<u8>>,
+    {
+        let value = String::deserialize(d)?;
+        let bytes = hex::decode(&value).map_err(Error::custom)?;
+        T::try_from(bytes).map_err(|_| Error::custom("hex string has the wrong length"))
+    }
+}
+
+/// Encode a list of byte containers, such as signatures or guardian keys, as a list of hex strings.
+#[cfg(feature = "serde")]
+pub mod hex_list {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::convert::TryFrom; // Remove in 2021
+    use serde::de::Error;
+    use serde::ser::SerializeSeq;
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serializer,
+    };
+
+    pub fn serialize<S: Serializer, T: AsRef<[u8]>>(
+        value: &[T],
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(value.len()))?;
+        for item in value {
+            seq.serialize_element(&hex::encode(item))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D, T>(d: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<Vec<u8>>,
+    {
+        Vec::<String>::deserialize(d)?
+            .iter()
+            .map(|value| {
+                let bytes = hex::decode(value).map_err(Error::custom)?;
+                T::try_from(bytes).map_err(|_| Error::custom("hex string has the wrong length"))
+            })
+            .collect()
+    }
+}
+
+/// Encode a U256 as a decimal string, JSON numbers cannot safely represent 256 bit values.
+#[cfg(feature = "serde")]
+pub mod u256_decimal {
+    use alloc::string::{
+        String,
+        ToString,
+    };
+    use primitive_types::U256;
+    use serde::de::Error;
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serializer,
+    };
+
+    pub fn serialize<S: Serializer>(value: &U256, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<U256, D::Error> {
+        let value = String::deserialize(d)?;
+        U256::from_dec_str(&value).map_err(|_| Error::custom("invalid decimal U256"))
+    }
+}
+
+#[cfg(feature = "borsh")]
+pub(crate) fn borsh_serialize_u256<W: borsh::maybestd::io::Write>(
+    value: &primitive_types::U256,
+    writer: &mut W,
+) -> borsh::maybestd::io::Result<()> {
+    let mut bytes = [0u8; 32];
+    value.to_little_endian(&mut bytes);
+    writer.write_all(&bytes)
+}
+
+#[cfg(feature = "borsh")]
+pub(crate) fn borsh_deserialize_u256(
+    buf: &mut &[u8],
+) -> borsh::maybestd::io::Result<primitive_types::U256> {
+    let bytes: [u8; 32] = borsh::BorshDeserialize::deserialize(buf)?;
+    Ok(primitive_types::U256::from_little_endian(&bytes))
+}
+
+#[cfg(test)]
+mod testing {
+    use crate::token::Transfer;
+    use crate::{
+        Chain,
+        VAA,
+    };
+    use alloc::vec;
+    use primitive_types::U256;
+
+    fn transfer() -> Transfer {
+        Transfer {
+            amount:        U256::from_dec_str("1000000000000000000000000").unwrap(),
+            token_address: [1u8; 32],
+            token_chain:   Chain::Ethereum,
+            to:            [2u8; 32],
+            to_chain:      Chain::Unknown(99),
+            fee:           U256::from(7u64),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_json() {
+        let transfer = transfer();
+        let json = serde_json::to_value(&transfer).unwrap();
+        assert_eq!(json["amount"], "1000000000000000000000000");
+        assert_eq!(json["token_address"], hex::encode([1u8; 32]));
+        assert_eq!(json["token_chain"], 2);
+        assert_eq!(json["to_chain"], 99);
+        assert_eq!(serde_json::from_value::<Transfer>(json).unwrap(), transfer);
+
+        let vaa = VAA {
+            signatures: vec![[3u8; 66]],
+            payload: transfer.to_bytes(),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&vaa).unwrap();
+        assert_eq!(serde_json::from_str::<VAA>(&json).unwrap(), vaa);
+
+        // Addresses of the wrong length are rejected.
+        let json = serde_json::json!({
+            "version": 1,
+            "guardian_set_index": 0,
+            "signatures": [],
+            "timestamp": 0,
+            "nonce": 0,
+            "emitter_chain": 1,
+            "emitter_address": "00ff",
+            "sequence": 0,
+            "consistency_level": 0,
+            "payload": "",
+        });
+        assert!(serde_json::from_value::<VAA>(json).is_err());
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn test_borsh() {
+        use borsh::{
+            BorshDeserialize,
+            BorshSerialize,
+        };
+
+        let transfer = transfer();
+        let bytes = transfer.try_to_vec().unwrap();
+        assert_eq!(Transfer::try_from_slice(&bytes).unwrap(), transfer);
+
+        let vaa = VAA {
+            signatures: vec![[3u8; 66]],
+            emitter_chain: Chain::Terra,
+            payload: transfer.to_bytes(),
+            ..Default::default()
+        };
+        let bytes = vaa.try_to_vec().unwrap();
+        assert_eq!(VAA::try_from_slice(&bytes).unwrap(), vaa);
+    }
+}
@@ -1,22 +1,105 @@
+use core::fmt;
+
 /// Ergonomic error handler for use within the Wormhole core/SDK libraries.
 #[macro_export]
 macro_rules! require {
-    ($expr:expr, $name:ident) => {
+    ($expr:expr, $err:expr) => {
         if !$expr {
-            return Err($name.into());
+            return Err($err.into());
         }
     }
 }
 
-/// This ErrorCode maps to the nom ParseError, we use an integer because the library is deprecating
-/// the current error type, so we should avoid depending on it for now.
-type ErrorCode = usize;
+/// Position of a guardian within a guardian set, as found in the first byte of a VAA signature.
+type GuardianIndex = u8;
+
+/// Identifies the field of a wire format message that failed to parse.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Field {
+    /// A named field, such as `emitter_chain` or `amount`.
+    Named(&'static str),
+
+    /// The signature at the given position in the signature list of a VAA.
+    Signature(u8),
+}
+
+impl From<&'static str> for Field {
+    fn from(name: &'static str) -> Field {
+        Field::Named(name)
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Field::Named(name) => f.write_str(name),
+            Field::Signature(position) => write!(f, "signature {}", position),
+        }
+    }
+}
 
-#[derive(Debug)]
+/// Parse errors report the offset at which parsing failed, counted from the start of the message
+/// being parsed. For payload parsers this is the start of the payload rather than of the VAA.
+#[derive(Debug, PartialEq)]
 pub enum WormholeError {
     InvalidGovernanceAction,
     InvalidGovernanceChain,
     InvalidGovernanceModule,
+    InvalidChain,
     DeserializeFailed,
-    ParseError(ErrorCode),
+
+    // Parsing
+    InvalidField { field: Field, offset: usize },
+    TrailingBytes { offset: usize },
+    Truncated { field: Field, offset: usize },
+
+    // Signature Verification
+    DuplicateGuardianIndex(GuardianIndex),
+    GuardianIndexOutOfRange(GuardianIndex),
+    InvalidSignature(GuardianIndex),
+    NoQuorum { signatures: usize, required: usize },
+    SignatureMismatch(GuardianIndex),
+    UnorderedGuardianIndex(GuardianIndex),
+}
+
+impl fmt::Display for WormholeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use WormholeError::*;
+        match self {
+            InvalidGovernanceAction => f.write_str("unexpected governance action"),
+            InvalidGovernanceChain => f.write_str("governance action targets another chain"),
+            InvalidGovernanceModule => f.write_str("unexpected governance module"),
+            InvalidChain => f.write_str("unknown chain"),
+            DeserializeFailed => f.write_str("failed to deserialize"),
+
+            InvalidField { field, offset } => {
+                write!(f, "invalid {} at byte {}", field, offset)
+            }
+            TrailingBytes { offset } => {
+                write!(f, "unexpected trailing bytes after byte {}", offset)
+            }
+            Truncated { field, offset } => {
+                write!(f, "input ended at byte {} while reading {}", offset, field)
+            }
+
+            DuplicateGuardianIndex(index) => write!(f, "duplicate signature by guardian {}", index),
+            GuardianIndexOutOfRange(index) => {
+                write!(f, "guardian {} is not in the guardian set", index)
+            }
+            InvalidSignature(index) => write!(f, "malformed signature by guardian {}", index),
+            NoQuorum {
+                signatures,
+                required,
+            } => write!(f, "{} signatures do not reach quorum of {}", signatures, required),
+            SignatureMismatch(index) => {
+                write!(f, "signature does not match guardian {}", index)
+            }
+            UnorderedGuardianIndex(index) => {
+                write!(f, "signature by guardian {} is out of order", index)
+            }
+        }
+    }
 }
+
+#[cfg(feature = "std")]
+impl std::error::Error for WormholeError {}
@@ -1,5 +1,14 @@
+//! Core types and parsers for the Wormhole protocol. The parsers, digests and governance actions
+//! only depend on `alloc`, so disabling the default `std` feature allows this crate to be used in
+//! `#![no_std]` environments that provide an allocator.
+
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(unused_results)]
 
+extern crate alloc;
+
+use alloc::string::String;
+
 pub use chain::*;
 pub use error::*;
 pub use vaa::*;
@@ -8,6 +17,9 @@ pub use vaa::*;
 pub mod chain;
 pub mod vaa;
 
+#[cfg(any(feature = "serde", feature = "borsh"))]
+pub mod encoding;
+
 #[macro_use]
 pub mod error;
 
@@ -18,19 +30,32 @@ pub mod error;
 ///
 /// This should be used to parse any Text-over-Wormhole fields that are meant to be human readable.
 pub(crate) fn parse_fixed_utf8<T: AsRef<[u8]>, const N: usize>(s: T) -> Option<String> {
-    use bstr::ByteSlice;
-    use std::io::Cursor;
-    use std::io::Read;
-
     // Read Bytes.
-    let mut cursor = Cursor::new(s.as_ref());
-    let mut buffer = vec![0u8; N];
-    cursor.read_exact(&mut buffer).ok()?;
+    let mut buffer = s.as_ref().get(..N)?.to_vec();
     buffer.retain(|&c| c != 0);
 
     // Attempt UTF-8 Decoding. Stripping invalid Unicode characters (0xFFFD).
-    let mut buffer: Vec<char> = buffer.chars().collect();
-    buffer.retain(|&c| c != '\u{FFFD}');
+    let mut buffer = String::from_utf8_lossy(&buffer).into_owned();
+    buffer.retain(|c| c != '\u{FFFD}');
+
+    Some(buffer)
+}
+
+/// Inverse of `parse_fixed_utf8`, writes a string into a fixed size field, zero padded on the
+/// right. Strings longer than the field are truncated.
+pub(crate) fn write_fixed_utf8<const N: usize>(s: &str) -> [u8; N] {
+    let s = truncate_utf8(s, N);
+    let mut buffer = [0u8; N];
+    buffer[..s.len()].copy_from_slice(s.as_bytes());
+    buffer
+}
 
-    Some(buffer.iter().collect())
+/// Truncate a string to at most `max` bytes without splitting a multi-byte character, so that the
+/// truncated bytes are still valid UTF-8 for the receiving side.
+pub(crate) fn truncate_utf8(s: &str, max: usize) -> &str {
+    let mut len = s.len().min(max);
+    while !s.is_char_boundary(len) {
+        len -= 1;
+    }
+    &s[..len]
 }
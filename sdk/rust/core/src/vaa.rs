@@ -9,38 +9,39 @@
 //! includes parsers for the core VAA type. Programs targetting wormhole can use this module to
 //! parse and verify incoming VAA's securely.
 
+use nom::bytes::complete::take;
 use nom::combinator::rest;
-use nom::error::{
-    Error,
-    ErrorKind,
-};
-use nom::multi::{
-    count,
-    fill,
-};
+use nom::multi::fill;
 use nom::number::complete::{
     u16,
     u32,
     u64,
     u8,
 };
+use nom::error::ErrorKind;
 use nom::number::Endianness;
-use nom::{
-    Err,
-    Finish,
-    IResult,
-};
-use std::convert::TryFrom;
-use std::str::FromStr; // Remove in 2021
+use nom::IResult;
+use alloc::vec::Vec;
+use ::core::convert::TryInto; // Remove in 2021
 
 use crate::WormholeError::{
+    DuplicateGuardianIndex,
+    GuardianIndexOutOfRange,
+    InvalidField,
     InvalidGovernanceAction,
     InvalidGovernanceChain,
     InvalidGovernanceModule,
+    InvalidSignature,
+    NoQuorum,
+    SignatureMismatch,
+    TrailingBytes,
+    Truncated,
+    UnorderedGuardianIndex,
 };
 use crate::{
     require,
     Chain,
+    Field,
     WormholeError,
 };
 
@@ -48,8 +49,14 @@ use crate::{
 
 pub mod core;
 pub mod nft;
+pub mod payload;
 pub mod token;
 
+pub use self::payload::{
+    Module,
+    Payload,
+};
+
 
 /// Signatures are typical ECDSA signatures prefixed with a Guardian position. These have the
 /// following byte layout:
@@ -60,6 +67,13 @@ pub mod token;
 /// ```
 pub type Signature = [u8; 66];
 
+/// Guardian secp256k1 private key, used to sign VAA's with `VAA::sign`.
+pub use libsecp256k1::SecretKey;
+
+/// Guardians are identified by ETH style addresses, the last 20 bytes of the Keccak hash of their
+/// secp256k1 public key.
+pub type GuardianAddress = [u8; 20];
+
 /// Wormhole specifies token addresses as 32 bytes. Addresses that are shorter, for example 20 byte
 /// Ethereum addresses, are left zero padded to 32.
 pub type ForeignAddress = [u8; 32];
@@ -67,28 +81,115 @@ pub type ForeignAddress = [u8; 32];
 /// Fields on VAA's are all usually fixed bytestrings, however they often contain UTF-8. When
 /// parsed these result in `String` with the additional constraint that they are always equal or
 /// less to the underlying byte field.
-type ShortUTFString = String;
+type ShortUTFString = alloc::string::String;
 
 /// The core VAA itself. This structure is what is received by a contract on the receiving side of
 /// a wormhole message passing flow. The payload of the message must be parsed separately to the
 /// VAA itself as it is completely user defined.
-#[derive(Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 pub struct VAA {
     // Header
     pub version:            u8,
     pub guardian_set_index: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_list"))]
     pub signatures:         Vec<Signature>,
 
     // Body
     pub timestamp:         u32,
     pub nonce:             u32,
     pub emitter_chain:     Chain,
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub emitter_address:   ForeignAddress,
     pub sequence:          u64,
     pub consistency_level: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub payload:           Vec<u8>,
 }
 
+/// VAABuilder is a convenience for constructing VAA's from scratch, for example when producing
+/// test fixtures or devnet governance messages. Fields not set default to zero, except for the
+/// version which defaults to the current VAA version.
+#[derive(Debug)]
+pub struct VAABuilder {
+    vaa: VAA,
+}
+
+impl Default for VAABuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VAABuilder {
+    pub fn new() -> Self {
+        VAABuilder {
+            vaa: VAA {
+                version: 1,
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn version(mut self, version: u8) -> Self {
+        self.vaa.version = version;
+        self
+    }
+
+    pub fn guardian_set_index(mut self, guardian_set_index: u32) -> Self {
+        self.vaa.guardian_set_index = guardian_set_index;
+        self
+    }
+
+    pub fn timestamp(mut self, timestamp: u32) -> Self {
+        self.vaa.timestamp = timestamp;
+        self
+    }
+
+    pub fn nonce(mut self, nonce: u32) -> Self {
+        self.vaa.nonce = nonce;
+        self
+    }
+
+    pub fn emitter_chain(mut self, emitter_chain: Chain) -> Self {
+        self.vaa.emitter_chain = emitter_chain;
+        self
+    }
+
+    pub fn emitter_address(mut self, emitter_address: ForeignAddress) -> Self {
+        self.vaa.emitter_address = emitter_address;
+        self
+    }
+
+    pub fn sequence(mut self, sequence: u64) -> Self {
+        self.vaa.sequence = sequence;
+        self
+    }
+
+    pub fn consistency_level(mut self, consistency_level: u8) -> Self {
+        self.vaa.consistency_level = consistency_level;
+        self
+    }
+
+    pub fn payload<T: Into<Vec<u8>>>(mut self, payload: T) -> Self {
+        self.vaa.payload = payload.into();
+        self
+    }
+
+    /// Produce the unsigned VAA.
+    pub fn build(self) -> VAA {
+        self.vaa
+    }
+
+    /// Produce the VAA signed by the given guardian keys, see `VAA::sign`.
+    pub fn sign(self, guardians: &[SecretKey]) -> VAA {
+        let mut vaa = self.vaa;
+        vaa.sign(guardians);
+        vaa
+    }
+}
+
 /// Contains the hash, secp256k1 payload, and serialized digest of the VAA. These are used in
 /// various places in Wormhole codebases.
 pub struct VAADigest {
@@ -99,10 +200,7 @@ pub struct VAADigest {
 impl VAA {
     /// Given any argument treatable as a series of bytes, attempt to deserialize into a valid VAA.
     pub fn from_bytes<T: AsRef<[u8]>>(input: T) -> Result<Self, WormholeError> {
-        match parse_vaa(input.as_ref()).finish() {
-            Ok(input) => Ok(input.1),
-            Err(e) => Err(WormholeError::ParseError(e.code as usize)),
-        }
+        parse_vaa_ref(input.as_ref()).map(VAA::from)
     }
 
     /// A VAA is distinguished by the unique hash of its deterministic components. This method
@@ -110,35 +208,17 @@ impl VAA {
     /// components for identifying unique VAA's, including the bridge, modules, and core guardian
     /// software.
     pub fn digest(&self) -> Option<VAADigest> {
-        use byteorder::{
-            BigEndian,
-            WriteBytesExt,
-        };
         use sha3::Digest;
-        use std::io::{
-            Cursor,
-            Write,
-        };
 
         // Hash Deterministic Pieces
-        let body = {
-            let mut v = Cursor::new(Vec::new());
-            v.write_u32::<BigEndian>(self.timestamp).ok()?;
-            v.write_u32::<BigEndian>(self.nonce).ok()?;
-            v.write_u16::<BigEndian>(self.emitter_chain.clone() as u16).ok()?;
-            let _ = v.write(&self.emitter_address).ok()?;
-            v.write_u64::<BigEndian>(self.sequence).ok()?;
-            v.write_u8(self.consistency_level).ok()?;
-            let _ = v.write(&self.payload).ok()?;
-            v.into_inner()
-        };
+        let body = self.serialize_body();
 
         // We hash the body so that secp256k1 signatures are signing the hash instead of the body
         // within our contracts. We do this so we don't have to submit the entire VAA for signature
         // verification, only the hash.
         let hash: [u8; 32] = {
             let mut h = sha3::Keccak256::default();
-            let _ = h.write(body.as_slice()).unwrap();
+            h.update(body.as_slice());
             h.finalize().into()
         };
 
@@ -147,6 +227,277 @@ impl VAA {
             hash,
         })
     }
+
+    /// Serialize the VAA, including its signatures, into Wormhole wire format. This is the inverse
+    /// of `from_bytes`.
+    ///
+    /// Panics if the VAA has more than 255 signatures, as the wire format cannot count them.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let body = self.serialize_body();
+        let signature_count: u8 = self
+            .signatures
+            .len()
+            .try_into()
+            .expect("a VAA can carry at most 255 signatures");
+        let mut v = Vec::with_capacity(6 + self.signatures.len() * 66 + body.len());
+        v.push(self.version);
+        v.extend_from_slice(&self.guardian_set_index.to_be_bytes());
+        v.push(signature_count);
+        for signature in &self.signatures {
+            v.extend_from_slice(signature);
+        }
+        v.extend_from_slice(&body);
+        v
+    }
+
+    /// Serialize the deterministic body of the VAA, which is the part that guardians sign.
+    fn serialize_body(&self) -> Vec<u8> {
+        let mut v = Vec::with_capacity(51 + self.payload.len());
+        v.extend_from_slice(&self.timestamp.to_be_bytes());
+        v.extend_from_slice(&self.nonce.to_be_bytes());
+        v.extend_from_slice(&u16::from(self.emitter_chain).to_be_bytes());
+        v.extend_from_slice(&self.emitter_address);
+        v.extend_from_slice(&self.sequence.to_be_bytes());
+        v.push(self.consistency_level);
+        v.extend_from_slice(&self.payload);
+        v
+    }
+
+    /// Sign the VAA body with a full set of guardian keys, replacing any existing signatures. The
+    /// position of each key in `guardians` is used as its guardian index, so the produced
+    /// signatures are always in the ascending order the contracts require.
+    ///
+    /// Panics if more than 256 keys are given, as guardian indices are a single byte.
+    pub fn sign(&mut self, guardians: &[SecretKey]) {
+        assert!(
+            guardians.len() <= 256,
+            "guardian indices are limited to a single byte"
+        );
+
+        // Guardians sign the hash of the body hash, see `verify`.
+        let hash = keccak256(self.serialize_body());
+        let message = libsecp256k1::Message::parse(&keccak256(hash));
+
+        self.signatures = guardians
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let (signature, recovery_id) = libsecp256k1::sign(&message, key);
+                let mut out = [0u8; 66];
+                out[0] = index as u8;
+                out[1..65].copy_from_slice(&signature.serialize());
+                out[65] = recovery_id.serialize();
+                out
+            })
+            .collect();
+    }
+
+    /// Verify the signatures on this VAA against a guardian set, where the guardian set is given as
+    /// the list of ETH style addresses stored by the contracts. This applies the same signature
+    /// checks as the on-chain contracts: signatures must be ordered by strictly ascending guardian
+    /// index, every signature must recover to the guardian at that index, and there must be enough
+    /// signatures to reach quorum.
+    ///
+    /// The contracts also reject VAAs signed by a guardian set that has expired, which is left to
+    /// the caller as only the addresses of the set are given here.
+    pub fn verify(&self, guardian_set: &[GuardianAddress]) -> Result<(), WormholeError> {
+        let digest = self.digest().ok_or(WormholeError::DeserializeFailed)?;
+        let signatures: Vec<&Signature> = self.signatures.iter().collect();
+        verify_signatures(&signatures, digest.hash, guardian_set, None)
+    }
+
+    /// As `verify`, but checks each signature against the address previously recovered from it
+    /// with `recover_signer`, in signature order, rather than recovering it again. Key recovery
+    /// dominates the cost of verification, so this allows callers to cache recovered addresses.
+    pub fn verify_signers(
+        &self,
+        signers: &[GuardianAddress],
+        guardian_set: &[GuardianAddress],
+    ) -> Result<(), WormholeError> {
+        let digest = self.digest().ok_or(WormholeError::DeserializeFailed)?;
+        let signatures: Vec<&Signature> = self.signatures.iter().collect();
+        verify_signatures(&signatures, digest.hash, guardian_set, Some(signers))
+    }
+}
+
+/// VAARef is a zero-copy view of a VAA, borrowing the signatures and payload directly from the
+/// buffer it was parsed from. This is useful in high throughput or on-chain settings where the
+/// allocations made by `VAA::from_bytes` are undesirable. It can be cheaply converted into an
+/// owned `VAA` when needed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VAARef<'a> {
+    // Header
+    pub version:            u8,
+    pub guardian_set_index: u32,
+    signatures:             &'a [u8],
+
+    // Body
+    pub timestamp:         u32,
+    pub nonce:             u32,
+    pub emitter_chain:     Chain,
+    pub emitter_address:   ForeignAddress,
+    pub sequence:          u64,
+    pub consistency_level: u8,
+    pub payload:           &'a [u8],
+
+    // The original body bytes, kept so the digest does not need to re-serialize them.
+    body: &'a [u8],
+}
+
+impl<'a> VAARef<'a> {
+    /// Parse a VAA without copying its signatures or payload out of `input`.
+    pub fn from_bytes(input: &'a [u8]) -> Result<Self, WormholeError> {
+        parse_vaa_ref(input)
+    }
+
+    /// Iterate over the signatures in the order they appear on the wire.
+    pub fn signatures(&self) -> impl Iterator<Item = &'a Signature> + 'a {
+        self.signatures
+            .chunks_exact(66)
+            .filter_map(|signature| signature.try_into().ok())
+    }
+
+    /// Number of signatures attached to the VAA.
+    pub fn signature_count(&self) -> usize {
+        self.signatures.len() / 66
+    }
+
+    /// The signed body of the VAA, exactly as it appeared in the input.
+    pub fn body(&self) -> &'a [u8] {
+        self.body
+    }
+
+    /// See `VAA::digest`, the hash here is taken directly over the original body bytes.
+    pub fn digest(&self) -> Option<VAADigest> {
+        Some(VAADigest {
+            digest: self.body.to_vec(),
+            hash:   keccak256(self.body),
+        })
+    }
+
+    /// See `VAA::verify`.
+    pub fn verify(&self, guardian_set: &[GuardianAddress]) -> Result<(), WormholeError> {
+        let signatures: Vec<&Signature> = self.signatures().collect();
+        let hash = keccak256(self.body);
+        verify_signatures(&signatures, hash, guardian_set, None)
+    }
+}
+
+impl From<VAARef<'_>> for VAA {
+    fn from(other: VAARef<'_>) -> VAA {
+        VAA {
+            version:            other.version,
+            guardian_set_index: other.guardian_set_index,
+            signatures:         other.signatures().copied().collect(),
+            timestamp:          other.timestamp,
+            nonce:              other.nonce,
+            emitter_chain:      other.emitter_chain,
+            emitter_address:    other.emitter_address,
+            sequence:           other.sequence,
+            consistency_level:  other.consistency_level,
+            payload:            other.payload.to_vec(),
+        }
+    }
+}
+
+/// Shared signature verification for `VAA` and `VAARef`, see `VAA::verify` for the rules applied.
+/// The `hash` is the Keccak hash of the VAA body. Addresses are recovered from the signatures
+/// unless `signers` already holds them.
+fn verify_signatures(
+    signatures: &[&Signature],
+    hash: [u8; 32],
+    guardian_set: &[GuardianAddress],
+    signers: Option<&[GuardianAddress]>,
+) -> Result<(), WormholeError> {
+    // Check quorum up front, there is no point recovering keys for a VAA that cannot pass.
+    let required = quorum(guardian_set.len());
+    if signatures.len() < required {
+        return Err(NoQuorum {
+            signatures: signatures.len(),
+            required,
+        });
+    }
+
+    // Strictly ascending indices guarantee a guardian cannot be counted twice. These checks are
+    // cheap so they are done before any key recovery.
+    let mut last_index: Option<u8> = None;
+    for signature in signatures {
+        let index = signature[0];
+        match last_index {
+            Some(last) if index == last => return Err(DuplicateGuardianIndex(index)),
+            Some(last) if index < last => return Err(UnorderedGuardianIndex(index)),
+            _ => last_index = Some(index),
+        }
+        require!((index as usize) < guardian_set.len(), GuardianIndexOutOfRange(index));
+    }
+
+    // Guardians sign the hash of the body hash, so the digest must be hashed once more before it
+    // can be used to recover keys.
+    let message = libsecp256k1::Message::parse(&keccak256(hash));
+
+    for (position, signature) in signatures.iter().enumerate() {
+        let index = signature[0];
+        let recovered = match signers {
+            Some(signers) => signers.get(position).copied(),
+            None => recover_address(&message, signature),
+        };
+        let recovered = recovered.ok_or(InvalidSignature(index))?;
+        if recovered != guardian_set[index as usize] {
+            return Err(SignatureMismatch(index));
+        }
+    }
+
+    Ok(())
+}
+
+/// Number of signatures required to reach consensus for a guardian set of the given size, this is
+/// the 2/3+1 quorum enforced by all Wormhole contracts. The calculation is kept in the same
+/// expanded fixed-point form as the contracts to ease comparison.
+pub fn quorum(guardians: usize) -> usize {
+    // Allow a quorum of 0 for empty sets, matching the Terra contract.
+    if guardians == 0 {
+        return 0;
+    }
+
+    ((guardians * 10 / 3) * 2) / 10 + 1
+}
+
+/// Derive the ETH style address that identifies the guardian owning `secret` within a guardian
+/// set.
+pub fn guardian_address(secret: &SecretKey) -> GuardianAddress {
+    public_key_address(&libsecp256k1::PublicKey::from_secret_key(secret))
+}
+
+/// Recover the ETH style address of the guardian that produced a VAA signature, where `hash` is the
+/// Keccak hash of the VAA body as found in `VAADigest`. Returns None for malformed signatures.
+pub fn recover_signer(hash: &[u8; 32], signature: &Signature) -> Option<GuardianAddress> {
+    let message = libsecp256k1::Message::parse(&keccak256(hash));
+    recover_address(&message, signature)
+}
+
+/// Recover the ETH style address of the key that produced a VAA signature over `message`.
+fn recover_address(
+    message: &libsecp256k1::Message,
+    signature: &Signature,
+) -> Option<GuardianAddress> {
+    let recovery_id = libsecp256k1::RecoveryId::parse(signature[65]).ok()?;
+    let signature = libsecp256k1::Signature::parse_standard_slice(&signature[1..65]).ok()?;
+    let key = libsecp256k1::recover(message, &signature, &recovery_id).ok()?;
+    Some(public_key_address(&key))
+}
+
+/// Addresses are the last 20 bytes of the hash of the uncompressed key, minus its 0x04 prefix.
+fn public_key_address(key: &libsecp256k1::PublicKey) -> GuardianAddress {
+    let hash = keccak256(&key.serialize()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    address
+}
+
+#[inline]
+fn keccak256<T: AsRef<[u8]>>(input: T) -> [u8; 32] {
+    use sha3::Digest;
+    sha3::Keccak256::digest(input.as_ref()).into()
 }
 
 /// Using nom, parse a fixed array of bytes without any allocation. Useful for parsing addresses,
@@ -163,50 +514,126 @@ pub fn parse_fixed<const S: usize>(input: &[u8]) -> IResult<&[u8], [u8; S]> {
 #[inline]
 pub fn parse_chain(input: &[u8]) -> IResult<&[u8], Chain> {
     let (i, chain) = u16(Endianness::Big)(input)?;
-    let chain = Chain::try_from(chain).map_err(|_| Err::Error(Error::new(i, ErrorKind::NoneOf)))?;
-    Ok((i, chain))
+    Ok((i, Chain::from(chain)))
 }
 
-/// Parse a VAA from a vector of raw bytes. Nom handles situations where the data is either too
-/// short or too long.
+/// Run a nom parser over a single field of `message`, starting at `input`. Failures are converted
+/// into errors naming the field and the offset within `message` at which parsing failed, running
+/// out of input is reported as `Truncated` and anything else as `InvalidField`.
 #[inline]
-fn parse_vaa(input: &[u8]) -> IResult<&[u8], VAA> {
-    let (i, version) = u8(input)?;
-    let (i, guardian_set_index) = u32(Endianness::Big)(i)?;
-    let (i, signature_count) = u8(i)?;
-    let (i, signatures) = count(parse_fixed, signature_count.into())(i)?;
-    let (i, timestamp) = u32(Endianness::Big)(i)?;
-    let (i, nonce) = u32(Endianness::Big)(i)?;
-    let (i, emitter_chain) = parse_chain(i)?;
-    let (i, emitter_address) = parse_fixed(i)?;
-    let (i, sequence) = u64(Endianness::Big)(i)?;
-    let (i, consistency_level) = u8(i)?;
-    let (i, payload) = rest(i)?;
-    Ok((
-        i,
-        VAA {
-            version,
-            guardian_set_index,
-            signatures,
-            timestamp,
-            nonce,
-            emitter_chain,
-            emitter_address,
-            sequence,
-            consistency_level,
-            payload: payload.to_vec(),
-        },
-    ))
+pub(crate) fn parse_field<'a, O>(
+    message: &'a [u8],
+    input: &'a [u8],
+    field: impl Into<Field>,
+    mut parser: impl FnMut(&'a [u8]) -> IResult<&'a [u8], O>,
+) -> Result<(&'a [u8], O), WormholeError> {
+    parser(input).map_err(|e| {
+        let field = field.into();
+        match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                let offset = message.len() - e.input.len();
+                match e.code {
+                    ErrorKind::Eof => Truncated { field, offset },
+                    _ => InvalidField { field, offset },
+                }
+            }
+            nom::Err::Incomplete(_) => Truncated {
+                field,
+                offset: message.len(),
+            },
+        }
+    })
+}
+
+/// Reject bytes left over after a complete message has been parsed from `message`, `rest` being
+/// whatever the parser did not consume.
+#[inline]
+pub(crate) fn require_consumed(message: &[u8], rest: &[u8]) -> Result<(), WormholeError> {
+    require!(
+        rest.is_empty(),
+        TrailingBytes {
+            offset: message.len() - rest.len(),
+        }
+    );
+    Ok(())
+}
+
+/// Parse a VAA, borrowing from the input rather than allocating. The owned parser is built on top
+/// of this so that both always agree on the wire format.
+#[inline]
+fn parse_vaa_ref(input: &[u8]) -> Result<VAARef<'_>, WormholeError> {
+    let (i, version) = parse_field(input, input, "version", u8)?;
+    let (i, guardian_set_index) =
+        parse_field(input, i, "guardian_set_index", u32(Endianness::Big))?;
+    let (i, signature_count) = parse_field(input, i, "signature_count", u8)?;
+
+    // Signatures are read one at a time so that a truncated VAA reports which one is incomplete.
+    let mut body = i;
+    for position in 0..signature_count {
+        let (next, _) = parse_field(input, body, Field::Signature(position), take(66usize))?;
+        body = next;
+    }
+    let signatures = &i[..i.len() - body.len()];
+
+    let (i, timestamp) = parse_field(input, body, "timestamp", u32(Endianness::Big))?;
+    let (i, nonce) = parse_field(input, i, "nonce", u32(Endianness::Big))?;
+    let (i, emitter_chain) = parse_field(input, i, "emitter_chain", parse_chain)?;
+    let (i, emitter_address) = parse_field(input, i, "emitter_address", parse_fixed)?;
+    let (i, sequence) = parse_field(input, i, "sequence", u64(Endianness::Big))?;
+    let (i, consistency_level) = parse_field(input, i, "consistency_level", u8)?;
+    let (_, payload) = parse_field(input, i, "payload", rest)?;
+    Ok(VAARef {
+        version,
+        guardian_set_index,
+        signatures,
+        timestamp,
+        nonce,
+        emitter_chain,
+        emitter_address,
+        sequence,
+        consistency_level,
+        payload,
+        body,
+    })
 }
 
 /// All current Wormhole programs using Governance are prefixed with a Governance header with a
 /// consistent format.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GovHeader {
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub module: [u8; 32],
     pub action: u8,
     pub chains: Chain,
 }
 
+impl GovHeader {
+    /// Serialize the header to Wormhole wire format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::with_capacity(35);
+        v.extend_from_slice(&self.module);
+        v.push(self.action);
+        v.extend_from_slice(&u16::from(self.chains).to_be_bytes());
+        v
+    }
+}
+
+/// Left 0-pad a governance module name to the 32 bytes used on the wire.
+#[inline]
+fn pad_module(name: &[u8]) -> [u8; 32] {
+    let mut module = [0u8; 32];
+    module[32 - name.len()..].copy_from_slice(name);
+    module
+}
+
+/// Implemented by payloads that can be parsed directly from the payload of a VAA, such as token
+/// transfers. Governance actions are parsed with `GovernanceAction::from_bytes` instead, as they
+/// also carry a header.
+pub trait FromPayload: Sized {
+    fn from_payload(payload: &[u8]) -> Result<Self, WormholeError>;
+}
+
 pub trait GovernanceAction: Sized {
     const ACTION: u8;
     const MODULE: &'static [u8];
@@ -214,57 +641,95 @@ pub trait GovernanceAction: Sized {
     /// Implement a nom parser for the Action.
     fn parse(input: &[u8]) -> IResult<&[u8], Self>;
 
-    /// Serialize to Wormhole wire format.
-    /// fn serialize(&self) -> Result<Vec<u8>, WormholeError>;
+    /// Serialize the Action, without its governance header, to Wormhole wire format.
+    fn serialize(&self, output: &mut Vec<u8>);
+
+    /// Serialize the Action as a complete governance payload targetting `chain`. Chain::All
+    /// produces an action valid on every chain.
+    fn to_bytes(&self, chain: Chain) -> Vec<u8> {
+        let header = GovHeader {
+            module: pad_module(Self::MODULE),
+            action: Self::ACTION,
+            chains: chain,
+        };
+
+        let mut v = header.to_bytes();
+        self.serialize(&mut v);
+        v
+    }
 
-    /// Parses an Action from a governance payload securely.
+    /// Parses an Action from a governance payload securely. Like the on-chain parsers, payloads
+    /// with bytes following the Action are rejected.
     fn from_bytes<T: AsRef<[u8]>>(
         input: T,
         chain: Option<Chain>,
     ) -> Result<(GovHeader, Self), WormholeError> {
-        match parse_action(input.as_ref()).finish() {
-            Ok((_, (header, action))) => {
-                // If no Chain is given, we assume All, which implies always valid.
-                let chain = chain.unwrap_or(Chain::All);
-
-                // Left 0-pad the MODULE in case it is unpadded.
-                let mut module = [0u8; 32];
-                let modlen = Self::MODULE.len();
-                (&mut module[32 - modlen..]).copy_from_slice(&Self::MODULE);
-
-                // Verify Governance Data.
-                let valid_chain = chain == header.chains || chain == Chain::All;
-                let valid_action = header.action == Self::ACTION;
-                let valid_module = module == header.module;
-                require!(valid_action, InvalidGovernanceAction);
-                require!(valid_chain, InvalidGovernanceChain);
-                require!(valid_module, InvalidGovernanceModule);
-
-                Ok((header, action))
-            }
-            Err(e) => Err(WormholeError::ParseError(e.code as usize)),
-        }
+        let input = input.as_ref();
+        let (rest, header, action) = parse_governance(input, chain)?;
+        require_consumed(input, rest)?;
+        Ok((header, action))
+    }
+
+    /// As `from_bytes`, but ignores any bytes following the Action.
+    fn from_bytes_lenient<T: AsRef<[u8]>>(
+        input: T,
+        chain: Option<Chain>,
+    ) -> Result<(GovHeader, Self), WormholeError> {
+        let (_, header, action) = parse_governance(input.as_ref(), chain)?;
+        Ok((header, action))
     }
 }
 
+/// Parse and verify a governance payload, returning any bytes following the Action.
+fn parse_governance<A: GovernanceAction>(
+    input: &[u8],
+    chain: Option<Chain>,
+) -> Result<(&[u8], GovHeader, A), WormholeError> {
+    let (i, module) = parse_field(input, input, "module", parse_fixed)?;
+    let (i, action) = parse_field(input, i, "action", u8)?;
+    let (i, chains) = parse_field(input, i, "chain", parse_chain)?;
+    let (i, body) = parse_field(input, i, "governance action", A::parse)?;
+    let header = GovHeader {
+        module,
+        action,
+        chains,
+    };
+
+    // If no Chain is given, we assume All, which implies always valid.
+    let chain = chain.unwrap_or(Chain::All);
+
+    // Left 0-pad the MODULE in case it is unpadded.
+    let module = pad_module(A::MODULE);
+
+    // Verify Governance Data.
+    let valid_chain = chain == header.chains || chain == Chain::All;
+    let valid_action = header.action == A::ACTION;
+    let valid_module = module == header.module;
+    require!(valid_action, InvalidGovernanceAction);
+    require!(valid_chain, InvalidGovernanceChain);
+    require!(valid_module, InvalidGovernanceModule);
+
+    Ok((i, header, body))
+}
+
 #[inline]
 pub fn parse_action<A: GovernanceAction>(input: &[u8]) -> IResult<&[u8], (GovHeader, A)> {
-    let (i, header) = parse_governance_header(input.as_ref())?;
+    let (i, header) = parse_governance_header(input)?;
     let (i, action) = A::parse(i)?;
     Ok((i, (header, action)))
 }
 
 #[inline]
-pub fn parse_governance_header<'i, 'a>(input: &'i [u8]) -> IResult<&'i [u8], GovHeader> {
+pub fn parse_governance_header(input: &[u8]) -> IResult<&[u8], GovHeader> {
     let (i, module) = parse_fixed(input)?;
     let (i, action) = u8(i)?;
-    let (i, chains) = u16(Endianness::Big)(i)?;
+    let (i, chains) = parse_chain(i)?;
     Ok((
         i,
         GovHeader {
             module,
             action,
-            chains: Chain::try_from(chains).unwrap(),
+            chains,
         },
     ))
 }
@@ -272,16 +737,37 @@ pub fn parse_governance_header<'i, 'a>(input: &'i [u8]) -> IResult<&'i [u8], Gov
 #[cfg(test)]
 mod testing {
     use super::{
+        guardian_address,
         parse_governance_header,
+        quorum,
+        recover_signer,
         Chain,
+        SecretKey,
+        VAABuilder,
+        VAARef,
         VAA,
     };
+    use super::token;
+    use super::GovernanceAction;
+    use crate::Field;
+    use crate::WormholeError::{
+        DuplicateGuardianIndex,
+        GuardianIndexOutOfRange,
+        InvalidSignature,
+        NoQuorum,
+        SignatureMismatch,
+        Truncated,
+        UnorderedGuardianIndex,
+    };
+    use alloc::string::ToString;
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     #[test]
     fn test_valid_gov_header() {
-        let signers = hex::decode("00b072505b5b999c1d08905c02e2b6b2832ef72c0ba6c8db4f77fe457ef2b3d053410b1e92a9194d9210df24d987ac83d7b6f0c21ce90f8bc1869de0898bda7e9801").unwrap();
-        let payload = hex::decode("000000000000000000000000000000000000000000546f6b656e42726964676501000000013b26409f8aaded3f5ddca184695aa6a0fa829b0c85caf84856324896d214ca98").unwrap();
-        let emitter =
+        let _signers = hex::decode("00b072505b5b999c1d08905c02e2b6b2832ef72c0ba6c8db4f77fe457ef2b3d053410b1e92a9194d9210df24d987ac83d7b6f0c21ce90f8bc1869de0898bda7e9801").unwrap();
+        let _payload = hex::decode("000000000000000000000000000000000000000000546f6b656e42726964676501000000013b26409f8aaded3f5ddca184695aa6a0fa829b0c85caf84856324896d214ca98").unwrap();
+        let _emitter =
             hex::decode("0000000000000000000000000000000000000000000000000000000000000004")
                 .unwrap();
         let module =
@@ -301,20 +787,21 @@ mod testing {
         assert_eq!(header.chains, Chain::All);
     }
 
-    // Legacy VAA Signature Struct.
-    #[derive(Default, Clone)]
-    pub struct VAASignature {
-        pub signature:      Vec<u8>,
-        pub guardian_index: u8,
+    #[test]
+    fn test_gov_header_unknown_chain() {
+        // Governance targetting a chain newer than this library must still parse.
+        let payload = hex::decode("000000000000000000000000000000000000000000546f6b656e4272696467650100ff").unwrap();
+        let (_, header) = parse_governance_header(&payload).unwrap();
+        assert_eq!(header.chains, Chain::Unknown(0xff));
     }
 
     // Original VAA Parsing Code. Used to compare current code to old for parity.
+    #[cfg(feature = "std")]
     pub fn legacy_deserialize(data: &[u8]) -> std::result::Result<VAA, std::io::Error> {
         use byteorder::{
             BigEndian,
             ReadBytesExt,
         };
-        use std::convert::TryFrom;
         use std::io::Read;
 
         let mut rdr = std::io::Cursor::new(data);
@@ -334,7 +821,7 @@ mod testing {
         v.signatures = sigs;
         v.timestamp = rdr.read_u32::<BigEndian>()?;
         v.nonce = rdr.read_u32::<BigEndian>()?;
-        v.emitter_chain = Chain::try_from(rdr.read_u16::<BigEndian>()?).unwrap();
+        v.emitter_chain = Chain::from(rdr.read_u16::<BigEndian>()?);
         let mut emitter_address = [0u8; 32];
         rdr.read_exact(&mut emitter_address)?;
         v.emitter_address = emitter_address;
@@ -345,6 +832,7 @@ mod testing {
     }
 
     #[test]
+    #[cfg(feature = "std")]
     fn test_parse_vaa_parity() {
         // Decode VAA with old and new parsers, and compare result.
         let vaa = hex::decode("01000000000100b072505b5b999c1d08905c02e2b6b2832ef72c0ba6c8db4f77fe457ef2b3d053410b1e92a9194d9210df24d987ac83d7b6f0c21ce90f8bc1869de0898bda7e980100000001000000010001000000000000000000000000000000000000000000000000000000000000000400000000013c1bfa00000000000000000000000000000000000000000000546f6b656e42726964676501000000013b26409f8aaded3f5ddca184695aa6a0fa829b0c85caf84856324896d214ca98").unwrap();
@@ -382,4 +870,221 @@ mod testing {
     #[test]
     fn test_invalid_vaa() {
     }
+
+    #[test]
+    fn test_vaa_to_bytes() {
+        // Serializing a parsed VAA must reproduce the original wire format.
+        let bytes = hex::decode("01000000000100b072505b5b999c1d08905c02e2b6b2832ef72c0ba6c8db4f77fe457ef2b3d053410b1e92a9194d9210df24d987ac83d7b6f0c21ce90f8bc1869de0898bda7e980100000001000000010001000000000000000000000000000000000000000000000000000000000000000400000000013c1bfa00000000000000000000000000000000000000000000546f6b656e42726964676501000000013b26409f8aaded3f5ddca184695aa6a0fa829b0c85caf84856324896d214ca98").unwrap();
+        let vaa = VAA::from_bytes(&bytes).unwrap();
+        assert_eq!(vaa.to_bytes(), bytes);
+        assert_eq!(VAA::from_bytes(vaa.to_bytes()).unwrap(), vaa);
+    }
+
+    #[test]
+    fn test_sign_vaa() {
+        let secrets = [
+            SecretKey::parse(&[1u8; 32]).unwrap(),
+            SecretKey::parse(&[2u8; 32]).unwrap(),
+            SecretKey::parse(&[3u8; 32]).unwrap(),
+        ];
+        let guardians: Vec<_> = secrets.iter().map(guardian_address).collect();
+
+        let vaa = VAABuilder::new()
+            .guardian_set_index(2)
+            .emitter_chain(Chain::Terra)
+            .emitter_address([4u8; 32])
+            .payload(b"payload".to_vec())
+            .sign(&secrets);
+
+        // Signatures are produced in guardian order, and survive a round trip through bytes.
+        let indices: Vec<u8> = vaa.signatures.iter().map(|s| s[0]).collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+        let vaa = VAA::from_bytes(vaa.to_bytes()).unwrap();
+        assert_eq!(vaa.version, 1);
+        assert_eq!(vaa.guardian_set_index, 2);
+        assert_eq!(vaa.verify(&guardians), Ok(()));
+    }
+
+    #[test]
+    #[should_panic(expected = "at most 255 signatures")]
+    fn test_to_bytes_rejects_too_many_signatures() {
+        let vaa = VAA {
+            signatures: vec![[0u8; 66]; 256],
+            ..Default::default()
+        };
+        let _ = vaa.to_bytes();
+    }
+
+    #[test]
+    fn test_quorum() {
+        assert_eq!(quorum(0), 0);
+        assert_eq!(quorum(1), 1);
+        assert_eq!(quorum(2), 2);
+        assert_eq!(quorum(3), 3);
+        assert_eq!(quorum(4), 3);
+        assert_eq!(quorum(5), 4);
+        assert_eq!(quorum(6), 5);
+        assert_eq!(quorum(7), 5);
+        assert_eq!(quorum(19), 13);
+        assert_eq!(quorum(20), 14);
+        assert_eq!(quorum(100), 67);
+    }
+
+    // Devnet Guardian, used to sign VAAs within tests.
+    fn devnet_guardian() -> SecretKey {
+        let secret = hex::decode("cfb12303a19cde580bb4dd771639b0d26bc68353645571a8cff516ab2ee113a0");
+        SecretKey::parse_slice(&secret.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_verify_vaa() {
+        let guardian = guardian_address(&devnet_guardian());
+        assert_eq!(
+            hex::encode(guardian),
+            "befa429d57cd18b7f8a4d91a2da9ab4af05d0fbe"
+        );
+
+        let mut vaa = VAABuilder::new()
+            .timestamp(1)
+            .nonce(1)
+            .emitter_chain(Chain::Solana)
+            .sequence(20_716_538)
+            .payload(vec![1, 2, 3])
+            .sign(&[devnet_guardian()]);
+
+        // Valid against the signing guardian.
+        assert_eq!(vaa.verify(&[guardian]), Ok(()));
+
+        // Signed by a guardian not in the set.
+        assert_eq!(vaa.verify(&[[1u8; 20]]), Err(SignatureMismatch(0)));
+
+        // Signature refers to a guardian outside the set.
+        assert_eq!(
+            vaa.verify(&[]),
+            Err(GuardianIndexOutOfRange(0)),
+        );
+
+        // Not enough signatures for a set of three.
+        assert_eq!(
+            vaa.verify(&[guardian, [1u8; 20], [2u8; 20]]),
+            Err(NoQuorum {
+                signatures: 1,
+                required:   3,
+            })
+        );
+
+        // Addresses recovered ahead of time give the same result without recovering them again.
+        let hash = vaa.digest().unwrap().hash;
+        let signers = [recover_signer(&hash, &vaa.signatures[0]).unwrap()];
+        assert_eq!(signers, [guardian]);
+        assert_eq!(vaa.verify_signers(&signers, &[guardian]), Ok(()));
+        assert_eq!(
+            vaa.verify_signers(&[[1u8; 20]], &[guardian]),
+            Err(SignatureMismatch(0))
+        );
+        assert_eq!(
+            vaa.verify_signers(&[], &[guardian]),
+            Err(InvalidSignature(0))
+        );
+
+        // Any change to the body invalidates the signature.
+        vaa.nonce += 1;
+        assert_eq!(vaa.verify(&[guardian]), Err(SignatureMismatch(0)));
+    }
+
+    #[test]
+    fn test_vaa_ref() {
+        let guardian = guardian_address(&devnet_guardian());
+        let bytes = VAABuilder::new()
+            .timestamp(1)
+            .emitter_chain(Chain::Ethereum)
+            .sequence(1)
+            .payload(vec![1, 2, 3])
+            .sign(&[devnet_guardian()])
+            .to_bytes();
+
+        // The borrowed view must agree with the owned VAA in every respect.
+        let owned = VAA::from_bytes(&bytes).unwrap();
+        let view = VAARef::from_bytes(&bytes).unwrap();
+        assert_eq!(view.signature_count(), 1);
+        assert_eq!(view.signatures().next(), owned.signatures.first());
+        assert_eq!(view.payload, &owned.payload[..]);
+        assert_eq!(view.body(), &owned.digest().unwrap().digest[..]);
+        assert_eq!(view.digest().unwrap().hash, owned.digest().unwrap().hash);
+        assert_eq!(view.verify(&[guardian]), Ok(()));
+        assert_eq!(VAA::from(view), owned);
+
+        // Truncated signatures are rejected rather than read short.
+        assert!(VAARef::from_bytes(&bytes[..50]).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let bytes = VAABuilder::new()
+            .payload(vec![1, 2, 3])
+            .sign(&[devnet_guardian(), devnet_guardian()])
+            .to_bytes();
+
+        // Errors name the field being read and the offset at which the input ran out.
+        let truncated = |len: usize| VAA::from_bytes(&bytes[..len]).unwrap_err();
+        assert_eq!(
+            truncated(0),
+            Truncated {
+                field:  "version".into(),
+                offset: 0,
+            }
+        );
+        assert_eq!(
+            truncated(6 + 66 + 10),
+            Truncated {
+                field:  Field::Signature(1),
+                offset: 6 + 66,
+            }
+        );
+        assert_eq!(
+            truncated(6 + 132 + 9),
+            Truncated {
+                field:  "emitter_chain".into(),
+                offset: 6 + 132 + 8,
+            }
+        );
+        assert_eq!(
+            truncated(6 + 66 + 10).to_string(),
+            "input ended at byte 72 while reading signature 1"
+        );
+
+        // Governance offsets are relative to the start of the payload.
+        let payload = token::GovernanceContractUpgrade {
+            new_contract: [1u8; 32],
+        }
+        .to_bytes(Chain::All);
+        assert_eq!(
+            token::GovernanceContractUpgrade::from_bytes(&payload[..40], None).unwrap_err(),
+            Truncated {
+                field:  "governance action".into(),
+                offset: 40,
+            }
+        );
+    }
+
+    #[test]
+    fn test_verify_vaa_ordering() {
+        let guardians = [[0u8; 20], [1u8; 20]];
+
+        // Duplicate guardians are rejected before any key recovery takes place.
+        let vaa = VAA {
+            signatures: vec![[0u8; 66], [0u8; 66]],
+            ..Default::default()
+        };
+        assert_eq!(vaa.verify(&guardians), Err(DuplicateGuardianIndex(0)));
+
+        // As are descending guardian indices.
+        let mut second = [0u8; 66];
+        second[0] = 1;
+        let vaa = VAA {
+            signatures: vec![second, [0u8; 66]],
+            ..Default::default()
+        };
+        assert_eq!(vaa.verify(&guardians), Err(UnorderedGuardianIndex(0)));
+    }
 }
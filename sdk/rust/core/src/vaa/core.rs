@@ -15,14 +15,30 @@ use nom::number::complete::{
 };
 use nom::number::Endianness;
 use nom::IResult;
+use alloc::vec::Vec;
+use core::convert::TryInto;
 use primitive_types::U256;
 
 use crate::vaa::{
     parse_fixed,
     GovernanceAction,
 };
+#[cfg(feature = "borsh")]
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+#[cfg(feature = "borsh")]
+use crate::encoding::{
+    borsh_deserialize_u256,
+    borsh_serialize_u256,
+};
 
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 pub struct GovernanceContractUpgrade {
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub new_contract: [u8; 32],
 }
 
@@ -33,10 +49,18 @@ impl GovernanceAction for GovernanceContractUpgrade {
         let (i, new_contract) = parse_fixed(input)?;
         Ok((i, Self { new_contract }))
     }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.new_contract);
+    }
 }
 
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 pub struct GovernanceGuardianSetChange {
     pub new_guardian_set_index: u32,
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_list"))]
     pub new_guardian_set:       Vec<[u8; 20]>,
 }
 
@@ -55,9 +79,25 @@ impl GovernanceAction for GovernanceGuardianSetChange {
             },
         ))
     }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.new_guardian_set_index.to_be_bytes());
+        let len: u8 = self
+            .new_guardian_set
+            .len()
+            .try_into()
+            .expect("a guardian set can hold at most 255 guardians");
+        output.push(len);
+        for key in &self.new_guardian_set {
+            output.extend_from_slice(key);
+        }
+    }
 }
 
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GovernanceSetMessageFee {
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::u256_decimal"))]
     pub fee: U256,
 }
 
@@ -74,10 +114,20 @@ impl GovernanceAction for GovernanceSetMessageFee {
             },
         ))
     }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        let mut fee = [0u8; 32];
+        self.fee.to_big_endian(&mut fee);
+        output.extend_from_slice(&fee);
+    }
 }
 
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GovernanceTransferFees {
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::u256_decimal"))]
     pub amount: U256,
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub to:     [u8; 32],
 }
 
@@ -96,4 +146,246 @@ impl GovernanceAction for GovernanceTransferFees {
             },
         ))
     }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        let mut amount = [0u8; 32];
+        self.amount.to_big_endian(&mut amount);
+        output.extend_from_slice(&amount);
+        output.extend_from_slice(&self.to);
+    }
+}
+
+/// Set the number of seconds after its timestamp before a posted VAA can be closed to refund its
+/// rent. An expiration time of 0 means posted VAAs never expire.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+pub struct GovernanceSetPostedVAAExpiry {
+    pub expiration_time: u32,
+}
+
+impl GovernanceAction for GovernanceSetPostedVAAExpiry {
+    const MODULE: &'static [u8] = b"Core";
+    const ACTION: u8 = 5;
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (i, expiration_time) = u32(Endianness::Big)(input)?;
+        Ok((i, Self { expiration_time }))
+    }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.expiration_time.to_be_bytes());
+    }
+}
+
+/// Set the number of signatures required to reach consensus for the guardian set at
+/// `guardian_set_index`, which must be the current guardian set. The quorum may lower the default
+/// of 2/3+1 of the guardians but not exceed it, a quorum of 0 restores the default.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
+pub struct GovernanceSetQuorum {
+    pub guardian_set_index: u32,
+    pub quorum:             u8,
+}
+
+impl GovernanceAction for GovernanceSetQuorum {
+    const MODULE: &'static [u8] = b"Core";
+    const ACTION: u8 = 6;
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (i, guardian_set_index) = u32(Endianness::Big)(input)?;
+        let (i, quorum) = u8(i)?;
+        Ok((
+            i,
+            Self {
+                guardian_set_index,
+                quorum,
+            },
+        ))
+    }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.guardian_set_index.to_be_bytes());
+        output.push(self.quorum);
+    }
+}
+
+/// Set the fee charged for the messages of a single emitter, replacing the flat message fee. The
+/// fee is charged per message plus per byte of its payload.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GovernanceSetEmitterFee {
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
+    pub emitter:      [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::u256_decimal"))]
+    pub fee:          U256,
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::u256_decimal"))]
+    pub fee_per_byte: U256,
+}
+
+impl GovernanceAction for GovernanceSetEmitterFee {
+    const MODULE: &'static [u8] = b"Core";
+    const ACTION: u8 = 7;
+    fn parse(input: &[u8]) -> IResult<&[u8], Self> {
+        let (i, emitter) = parse_fixed(input)?;
+        let mut fee = [0u8; 32];
+        let (i, _) = fill(u8, &mut fee)(i)?;
+        let mut fee_per_byte = [0u8; 32];
+        let (i, _) = fill(u8, &mut fee_per_byte)(i)?;
+        Ok((
+            i,
+            Self {
+                emitter,
+                fee: U256::from_big_endian(&fee),
+                fee_per_byte: U256::from_big_endian(&fee_per_byte),
+            },
+        ))
+    }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.emitter);
+        let mut fee = [0u8; 32];
+        self.fee.to_big_endian(&mut fee);
+        output.extend_from_slice(&fee);
+        let mut fee_per_byte = [0u8; 32];
+        self.fee_per_byte.to_big_endian(&mut fee_per_byte);
+        output.extend_from_slice(&fee_per_byte);
+    }
+}
+
+// U256 has no borsh support of its own, so types carrying one implement borsh by hand, see
+// `crate::encoding`.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for GovernanceSetMessageFee {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        borsh_serialize_u256(&self.fee, writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for GovernanceSetMessageFee {
+    fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+        Ok(Self {
+            fee: borsh_deserialize_u256(buf)?,
+        })
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for GovernanceTransferFees {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        borsh_serialize_u256(&self.amount, writer)?;
+        BorshSerialize::serialize(&self.to, writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for GovernanceTransferFees {
+    fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+        Ok(Self {
+            amount: borsh_deserialize_u256(buf)?,
+            to: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for GovernanceSetEmitterFee {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        BorshSerialize::serialize(&self.emitter, writer)?;
+        borsh_serialize_u256(&self.fee, writer)?;
+        borsh_serialize_u256(&self.fee_per_byte, writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for GovernanceSetEmitterFee {
+    fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+        Ok(Self {
+            emitter:      BorshDeserialize::deserialize(buf)?,
+            fee:          borsh_deserialize_u256(buf)?,
+            fee_per_byte: borsh_deserialize_u256(buf)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::Chain;
+
+    #[test]
+    fn test_governance_round_trip() {
+        // Guardian set upgrade to index 1 with two guardians, targetting Solana.
+        let payload = hex::decode("00000000000000000000000000000000000000000000000000000000436f7265020001000000010213947bd48b18e53fdaeee77f3473391ac727c638167f4f4351d06f853f21c8f27057feb0ddcea3f6").unwrap();
+        let (header, action) = GovernanceGuardianSetChange::from_bytes(&payload, None).unwrap();
+        assert_eq!(header.chains, Chain::Solana);
+        assert_eq!(action.new_guardian_set_index, 1);
+        assert_eq!(action.new_guardian_set.len(), 2);
+        assert_eq!(action.to_bytes(Chain::Solana), payload);
+
+        let upgrade = GovernanceContractUpgrade {
+            new_contract: [7u8; 32],
+        };
+        let (_, parsed) =
+            GovernanceContractUpgrade::from_bytes(upgrade.to_bytes(Chain::All), None).unwrap();
+        assert_eq!(parsed, upgrade);
+
+        let fee = GovernanceSetMessageFee {
+            fee: U256::from(1_000_000u64),
+        };
+        let (_, parsed) =
+            GovernanceSetMessageFee::from_bytes(fee.to_bytes(Chain::Terra), Some(Chain::Terra))
+                .unwrap();
+        assert_eq!(parsed, fee);
+
+        let transfer = GovernanceTransferFees {
+            amount: U256::max_value(),
+            to:     [9u8; 32],
+        };
+        let (_, parsed) =
+            GovernanceTransferFees::from_bytes(transfer.to_bytes(Chain::All), None).unwrap();
+        assert_eq!(parsed, transfer);
+
+        let expiry = GovernanceSetPostedVAAExpiry {
+            expiration_time: 86400,
+        };
+        let bytes = expiry.to_bytes(Chain::Solana);
+        assert_eq!(&bytes[32..], &[5, 0, 1, 0, 1, 0x51, 0x80]);
+        let (_, parsed) =
+            GovernanceSetPostedVAAExpiry::from_bytes(bytes, Some(Chain::Solana)).unwrap();
+        assert_eq!(parsed, expiry);
+
+        let quorum = GovernanceSetQuorum {
+            guardian_set_index: 3,
+            quorum:             1,
+        };
+        let bytes = quorum.to_bytes(Chain::Solana);
+        assert_eq!(&bytes[32..], &[6, 0, 1, 0, 0, 0, 3, 1]);
+        let (_, parsed) = GovernanceSetQuorum::from_bytes(bytes, Some(Chain::Solana)).unwrap();
+        assert_eq!(parsed, quorum);
+
+        let emitter_fee = GovernanceSetEmitterFee {
+            emitter:      [4u8; 32],
+            fee:          U256::zero(),
+            fee_per_byte: U256::from(10u64),
+        };
+        let bytes = emitter_fee.to_bytes(Chain::Solana);
+        assert_eq!(&bytes[32..35], &[7, 0, 1]);
+        assert_eq!(bytes.len(), 35 + 32 * 3);
+        let (_, parsed) =
+            GovernanceSetEmitterFee::from_bytes(bytes, Some(Chain::Solana)).unwrap();
+        assert_eq!(parsed, emitter_fee);
+    }
 }
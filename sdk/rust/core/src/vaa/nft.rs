@@ -4,33 +4,52 @@
 //! supports, namely contract upgrades and chain registrations.
 
 use nom::bytes::complete::take;
-use nom::combinator::verify;
-use nom::number::complete::u8;
-use nom::{
-    Finish,
-    IResult,
+use nom::combinator::{
+    map_res,
+    verify,
 };
+use nom::number::complete::u8;
+use nom::IResult;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::str::from_utf8;
 use primitive_types::U256;
-use std::str::from_utf8;
 
 use crate::vaa::{
     parse_chain,
+    parse_field,
     parse_fixed,
+    require_consumed,
+    FromPayload,
     GovernanceAction,
 };
 use crate::vaa::ShortUTFString;
 use crate::{
-    Chain,
     parse_fixed_utf8,
+    truncate_utf8,
+    write_fixed_utf8,
+    Chain,
     WormholeError,
 };
+#[cfg(feature = "borsh")]
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+#[cfg(feature = "borsh")]
+use crate::encoding::{
+    borsh_deserialize_u256,
+    borsh_serialize_u256,
+};
 
 /// Transfer is a message containing specifics detailing a token lock up on a sending chain. Chains
 /// that are attempting to initiate a transfer must lock up tokens in some manner, such as in a
 /// custody account or via burning, before emitting this message.
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transfer {
     /// Address of the token. Left-zero-padded if shorter than 32 bytes
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub nft_address: [u8; 32],
 
     /// Chain ID of the token
@@ -43,12 +62,14 @@ pub struct Transfer {
     pub name: ShortUTFString,
 
     /// TokenID of the token (big-endian uint256)
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::u256_decimal"))]
     pub token_id: U256,
 
     /// URI of the token metadata
     pub uri: ShortUTFString,
 
     /// Address of the recipient. Left-zero-padded if shorter than 32 bytes
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub to: [u8; 32],
 
     /// Chain ID of the recipient
@@ -56,32 +77,67 @@ pub struct Transfer {
 }
 
 impl Transfer {
+    /// Parse a Transfer payload, rejecting any trailing bytes as the NFT bridge contracts do.
     pub fn from_bytes<T: AsRef<[u8]>>(input: T) -> Result<Self, WormholeError> {
-        match parse_payload_transfer(input.as_ref()).finish() {
-            Ok(input) => Ok(input.1),
-            Err(e) => Err(WormholeError::ParseError(e.code as usize)),
-        }
+        let input = input.as_ref();
+        let (rest, transfer) = parse_payload_transfer(input)?;
+        require_consumed(input, rest)?;
+        Ok(transfer)
+    }
+
+    /// As `from_bytes`, but ignores any bytes following the payload.
+    pub fn from_bytes_lenient<T: AsRef<[u8]>>(input: T) -> Result<Self, WormholeError> {
+        parse_payload_transfer(input.as_ref()).map(|(_, transfer)| transfer)
+    }
+
+    /// Serialize to Wormhole wire format, including the payload ID. Symbol and name are truncated
+    /// to 32 bytes, and the URI to 255 bytes as its length is encoded in a single byte. Truncation
+    /// never splits a character, so fields may end up a few bytes shorter than their limit.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut token_id = [0u8; 32];
+        self.token_id.to_big_endian(&mut token_id);
+        let uri = truncate_utf8(&self.uri, u8::MAX as usize).as_bytes();
+
+        let mut v = Vec::with_capacity(166 + uri.len());
+        v.push(0x1);
+        v.extend_from_slice(&self.nft_address);
+        v.extend_from_slice(&u16::from(self.nft_chain).to_be_bytes());
+        v.extend_from_slice(&write_fixed_utf8::<32>(&self.symbol));
+        v.extend_from_slice(&write_fixed_utf8::<32>(&self.name));
+        v.extend_from_slice(&token_id);
+        v.push(uri.len() as u8);
+        v.extend_from_slice(uri);
+        v.extend_from_slice(&self.to);
+        v.extend_from_slice(&u16::from(self.to_chain).to_be_bytes());
+        v
+    }
+}
+
+impl FromPayload for Transfer {
+    fn from_payload(payload: &[u8]) -> Result<Self, WormholeError> {
+        Self::from_bytes(payload)
     }
 }
 
-fn parse_payload_transfer(input: &[u8]) -> IResult<&[u8], Transfer> {
+fn parse_payload_transfer(input: &[u8]) -> Result<(&[u8], Transfer), WormholeError> {
     // Parse Payload
-    let (i, _) = verify(u8, |&s| s == 0x1)(input.as_ref())?;
-    let (i, nft_address) = parse_fixed(i)?;
-    let (i, nft_chain) = parse_chain(i)?;
-    let (i, symbol): (_, [u8; 32]) = parse_fixed(i)?;
-    let (i, name): (_, [u8; 32]) = parse_fixed(i)?;
-    let (i, token_id): (_, [u8; 32]) = parse_fixed(i)?;
-    let (i, uri_len) = u8(i)?;
-    let (i, uri) = take(uri_len)(i)?;
-    let (i, to) = parse_fixed(i)?;
-    let (i, to_chain) = parse_chain(i)?;
+    let (i, _) = parse_field(input, input, "payload_id", verify(u8, |&s| s == 0x1))?;
+    let (i, nft_address) = parse_field(input, i, "nft_address", parse_fixed)?;
+    let (i, nft_chain) = parse_field(input, i, "nft_chain", parse_chain)?;
+    let (i, symbol): (_, [u8; 32]) = parse_field(input, i, "symbol", parse_fixed)?;
+    let (i, name): (_, [u8; 32]) = parse_field(input, i, "name", parse_fixed)?;
+    let (i, token_id): (_, [u8; 32]) = parse_field(input, i, "token_id", parse_fixed)?;
+    let (i, uri_len) = parse_field(input, i, "uri_len", u8)?;
 
     // Name/Symbol and URI should be UTF-8 strings, attempt to parse the first two by removing
     // invalid bytes -- for the latter, assume UTF-8 and fail if unparseable.
+    let (i, uri) = parse_field(input, i, "uri", map_res(take(uri_len), from_utf8))?;
+    let (i, to) = parse_field(input, i, "to", parse_fixed)?;
+    let (i, to_chain) = parse_field(input, i, "to_chain", parse_chain)?;
+
     let name = parse_fixed_utf8::<_, 32>(name).unwrap();
     let symbol = parse_fixed_utf8::<_, 32>(symbol).unwrap();
-    let uri = from_utf8(uri).unwrap().to_string();
+    let uri = uri.to_string();
 
     Ok((
         i,
@@ -99,8 +155,11 @@ fn parse_payload_transfer(input: &[u8]) -> IResult<&[u8], Transfer> {
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 pub struct GovernanceRegisterChain {
     pub emitter:          Chain,
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub endpoint_address: [u8; 32],
 }
 
@@ -118,10 +177,18 @@ impl GovernanceAction for GovernanceRegisterChain {
             },
         ))
     }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&u16::from(self.emitter).to_be_bytes());
+        output.extend_from_slice(&self.endpoint_address);
+    }
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 pub struct GovernanceContractUpgrade {
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub new_contract: [u8; 32],
 }
 
@@ -132,4 +199,103 @@ impl GovernanceAction for GovernanceContractUpgrade {
         let (i, new_contract) = parse_fixed(input)?;
         Ok((i, Self { new_contract }))
     }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.new_contract);
+    }
+}
+
+// U256 has no borsh support of its own, so types carrying one implement borsh by hand, see
+// `crate::encoding`.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Transfer {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        BorshSerialize::serialize(&self.nft_address, writer)?;
+        BorshSerialize::serialize(&self.nft_chain, writer)?;
+        BorshSerialize::serialize(&self.symbol, writer)?;
+        BorshSerialize::serialize(&self.name, writer)?;
+        borsh_serialize_u256(&self.token_id, writer)?;
+        BorshSerialize::serialize(&self.uri, writer)?;
+        BorshSerialize::serialize(&self.to, writer)?;
+        BorshSerialize::serialize(&self.to_chain, writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Transfer {
+    fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+        Ok(Self {
+            nft_address: BorshDeserialize::deserialize(buf)?,
+            nft_chain: BorshDeserialize::deserialize(buf)?,
+            symbol: BorshDeserialize::deserialize(buf)?,
+            name: BorshDeserialize::deserialize(buf)?,
+            token_id: borsh_deserialize_u256(buf)?,
+            uri: BorshDeserialize::deserialize(buf)?,
+            to: BorshDeserialize::deserialize(buf)?,
+            to_chain: BorshDeserialize::deserialize(buf)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_payload_round_trip() {
+        let transfer = Transfer {
+            nft_address: [1u8; 32],
+            nft_chain:   Chain::Ethereum,
+            symbol:      "APE".to_string(),
+            name:        "Bored Ape".to_string(),
+            token_id:    U256::from(42u64),
+            uri:         "https://example.com/42.json".to_string(),
+            to:          [2u8; 32],
+            to_chain:    Chain::Solana,
+        };
+        assert_eq!(Transfer::from_bytes(transfer.to_bytes()).unwrap(), transfer);
+
+        let upgrade = GovernanceContractUpgrade {
+            new_contract: [3u8; 32],
+        };
+        let (_, parsed) =
+            GovernanceContractUpgrade::from_bytes(upgrade.to_bytes(Chain::Solana), None).unwrap();
+        assert_eq!(parsed, upgrade);
+
+        // URIs that are not valid UTF-8 are rejected rather than panicking.
+        let mut bytes = transfer.to_bytes();
+        bytes[132] = 0xff;
+        assert_eq!(
+            Transfer::from_bytes(bytes),
+            Err(WormholeError::InvalidField {
+                field:  "uri".into(),
+                offset: 132,
+            })
+        );
+    }
+
+    #[test]
+    fn test_truncation_keeps_utf8_valid() {
+        // Every character here is multi-byte, so a plain byte cut at 32 or 255 would split one.
+        let transfer = Transfer {
+            nft_address: [1u8; 32],
+            nft_chain:   Chain::Ethereum,
+            symbol:      "€".repeat(11),
+            name:        "名前".repeat(6),
+            token_id:    U256::from(42u64),
+            uri:         "https://example.com/".to_string() + &"é".repeat(200),
+            to:          [2u8; 32],
+            to_chain:    Chain::Solana,
+        };
+
+        let parsed = Transfer::from_bytes(transfer.to_bytes()).unwrap();
+        assert_eq!(parsed.symbol, "€".repeat(10));
+        assert_eq!(parsed.name, "名前".repeat(5));
+        assert_eq!(parsed.uri, "https://example.com/".to_string() + &"é".repeat(117));
+        assert_eq!(parsed.uri.len(), 254);
+    }
 }
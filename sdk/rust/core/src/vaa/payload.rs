@@ -0,0 +1,309 @@
+//! This module exposes a dispatcher that identifies which standard Wormhole payload a VAA carries
+//! and parses it, which is useful for tools that inspect arbitrary VAAs.
+//!
+//! Payloads are not self describing: both token and NFT transfers start with payload ID 1, so the
+//! emitter of the VAA decides how its payload is read. Governance payloads are the exception, they
+//! are prefixed with a `GovHeader` naming the module they target and can always be detected.
+//!
+//! Classifying a payload does not verify it. Callers must still check the VAA signatures, and that
+//! governance VAAs were emitted by the governance emitter, before acting on the result.
+
+use nom::Finish;
+
+use crate::vaa::{
+    core,
+    nft,
+    parse_governance_header,
+    token,
+    ForeignAddress,
+    GovHeader,
+    GovernanceAction,
+    VAA,
+};
+use crate::{
+    Chain,
+    WormholeError,
+};
+
+/// The Wormhole modules that emit standard payloads.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Module {
+    /// The core bridge governance emitter, whose VAAs carry governance actions for any module.
+    Core,
+    TokenBridge,
+    NFTBridge,
+}
+
+/// A parsed standard Wormhole payload.
+#[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Payload {
+    // Core Governance.
+    CoreContractUpgrade(GovHeader, core::GovernanceContractUpgrade),
+    GuardianSetChange(GovHeader, core::GovernanceGuardianSetChange),
+    SetMessageFee(GovHeader, core::GovernanceSetMessageFee),
+    TransferFees(GovHeader, core::GovernanceTransferFees),
+    SetPostedVAAExpiry(GovHeader, core::GovernanceSetPostedVAAExpiry),
+    SetQuorum(GovHeader, core::GovernanceSetQuorum),
+    SetEmitterFee(GovHeader, core::GovernanceSetEmitterFee),
+
+    // Token Bridge.
+    TokenTransfer(token::Transfer),
+    AssetMeta(token::AssetMeta),
+    TokenRegisterChain(GovHeader, token::GovernanceRegisterChain),
+    TokenContractUpgrade(GovHeader, token::GovernanceContractUpgrade),
+
+    // NFT Bridge.
+    NFTTransfer(nft::Transfer),
+    NFTRegisterChain(GovHeader, nft::GovernanceRegisterChain),
+    NFTContractUpgrade(GovHeader, nft::GovernanceContractUpgrade),
+
+    /// The payload is not a standard Wormhole payload, or its emitter is not known.
+    Unknown,
+}
+
+impl Payload {
+    /// Identify and parse the payload of a VAA.
+    ///
+    /// `known_emitters` maps emitters to the module they belong to. VAAs from a known emitter must
+    /// carry a payload of that module, otherwise an error is returned. VAAs from other emitters
+    /// are only parsed when they carry a governance header for a known module, and are classified
+    /// as `Payload::Unknown` otherwise.
+    pub fn classify(
+        vaa: &VAA,
+        known_emitters: &[(Chain, ForeignAddress, Module)],
+    ) -> Result<Payload, WormholeError> {
+        let module = known_emitters
+            .iter()
+            .find(|(chain, address, _)| {
+                *chain == vaa.emitter_chain && *address == vaa.emitter_address
+            })
+            .map(|(_, _, module)| *module);
+
+        let payload = &vaa.payload[..];
+        match module {
+            Some(Module::Core) => {
+                classify_governance(payload).unwrap_or(Err(WormholeError::InvalidGovernanceModule))
+            }
+
+            // Anything other than an AssetMeta is parsed as a Transfer, which reports unknown or
+            // missing payload IDs.
+            Some(Module::TokenBridge) => match payload.first() {
+                Some(2) => token::AssetMeta::from_bytes(payload).map(Payload::AssetMeta),
+                _ => token::Transfer::from_bytes(payload).map(Payload::TokenTransfer),
+            },
+
+            Some(Module::NFTBridge) => nft::Transfer::from_bytes(payload).map(Payload::NFTTransfer),
+
+            None => classify_governance(payload).unwrap_or(Ok(Payload::Unknown)),
+        }
+    }
+}
+
+/// Parse a governance payload, dispatching on the module name and action in its header. Returns
+/// None if the payload does not start with a header for a known module.
+fn classify_governance(payload: &[u8]) -> Option<Result<Payload, WormholeError>> {
+    let (_, header) = parse_governance_header(payload).finish().ok()?;
+
+    // Module names are left 0-padded on the wire.
+    let start = header.module.iter().position(|&c| c != 0).unwrap_or(32);
+    let result = match (&header.module[start..], header.action) {
+        (b"Core", 1) => parse(payload, Payload::CoreContractUpgrade),
+        (b"Core", 2) => parse(payload, Payload::GuardianSetChange),
+        (b"Core", 3) => parse(payload, Payload::SetMessageFee),
+        (b"Core", 4) => parse(payload, Payload::TransferFees),
+        (b"Core", 5) => parse(payload, Payload::SetPostedVAAExpiry),
+        (b"Core", 6) => parse(payload, Payload::SetQuorum),
+        (b"Core", 7) => parse(payload, Payload::SetEmitterFee),
+        (b"TokenBridge", 1) => parse(payload, Payload::TokenRegisterChain),
+        (b"TokenBridge", 2) => parse(payload, Payload::TokenContractUpgrade),
+        (b"NFTBridge", 1) => parse(payload, Payload::NFTRegisterChain),
+        (b"NFTBridge", 2) => parse(payload, Payload::NFTContractUpgrade),
+        (b"Core", _) | (b"TokenBridge", _) | (b"NFTBridge", _) => {
+            Err(WormholeError::InvalidGovernanceAction)
+        }
+        _ => return None,
+    };
+
+    Some(result)
+}
+
+#[inline]
+fn parse<A: GovernanceAction>(
+    payload: &[u8],
+    variant: fn(GovHeader, A) -> Payload,
+) -> Result<Payload, WormholeError> {
+    A::from_bytes(payload, None).map(|(header, action)| variant(header, action))
+}
+
+#[cfg(test)]
+mod testing {
+    use super::{
+        Module,
+        Payload,
+    };
+    use crate::vaa::{
+        core,
+        nft,
+        token,
+        GovernanceAction,
+        VAABuilder,
+    };
+    use crate::{
+        Chain,
+        WormholeError,
+    };
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use primitive_types::U256;
+
+    const GOVERNANCE: [u8; 32] = {
+        let mut address = [0u8; 32];
+        address[31] = 4;
+        address
+    };
+
+    const EMITTERS: &[(Chain, [u8; 32], Module)] = &[
+        (Chain::Solana, GOVERNANCE, Module::Core),
+        (Chain::Ethereum, [1u8; 32], Module::TokenBridge),
+        (Chain::Ethereum, [2u8; 32], Module::NFTBridge),
+    ];
+
+    fn classify(
+        chain: Chain,
+        emitter: [u8; 32],
+        payload: Vec<u8>,
+    ) -> Result<Payload, WormholeError> {
+        let vaa = VAABuilder::new()
+            .emitter_chain(chain)
+            .emitter_address(emitter)
+            .payload(payload)
+            .build();
+
+        Payload::classify(&vaa, EMITTERS)
+    }
+
+    #[test]
+    fn test_classify_transfers() {
+        let transfer = token::Transfer {
+            amount:        U256::from(100u64),
+            token_address: [3u8; 32],
+            token_chain:   Chain::Ethereum,
+            to:            [4u8; 32],
+            to_chain:      Chain::Terra,
+            fee:           U256::zero(),
+        };
+
+        let nft = nft::Transfer {
+            nft_address: [3u8; 32],
+            nft_chain:   Chain::Ethereum,
+            symbol:      "NFT".into(),
+            name:        "Wormhole NFT".into(),
+            token_id:    U256::from(42u64),
+            uri:         "https://example.com/42".into(),
+            to:          [4u8; 32],
+            to_chain:    Chain::Solana,
+        };
+
+        // The emitter decides how a payload starting with ID 1 is read.
+        assert_eq!(
+            classify(Chain::Ethereum, [1u8; 32], transfer.to_bytes()).unwrap(),
+            Payload::TokenTransfer(transfer.clone())
+        );
+        assert_eq!(
+            classify(Chain::Ethereum, [2u8; 32], nft.to_bytes()).unwrap(),
+            Payload::NFTTransfer(nft)
+        );
+
+        // Unknown emitters, and known emitters on another chain, are not guessed at.
+        assert_eq!(
+            classify(Chain::Ethereum, [9u8; 32], transfer.to_bytes()).unwrap(),
+            Payload::Unknown
+        );
+        assert_eq!(
+            classify(Chain::Terra, [1u8; 32], transfer.to_bytes()).unwrap(),
+            Payload::Unknown
+        );
+
+        // Known emitters must carry a payload of their module.
+        assert_eq!(
+            classify(Chain::Ethereum, [2u8; 32], vec![2, 0, 0]),
+            Err(WormholeError::InvalidField {
+                field:  "payload_id".into(),
+                offset: 0,
+            })
+        );
+        assert_eq!(
+            classify(Chain::Ethereum, [1u8; 32], vec![]),
+            Err(WormholeError::Truncated {
+                field:  "payload_id".into(),
+                offset: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_classify_governance() {
+        let upgrade = token::GovernanceContractUpgrade {
+            new_contract: [5u8; 32],
+        };
+        let payload = upgrade.to_bytes(Chain::Terra);
+
+        // Governance is detected by its header, whoever the emitter is.
+        for emitter in [GOVERNANCE, [9u8; 32]] {
+            match classify(Chain::Solana, emitter, payload.clone()).unwrap() {
+                Payload::TokenContractUpgrade(header, action) => {
+                    assert_eq!(header.chains, Chain::Terra);
+                    assert_eq!(action, upgrade);
+                }
+                payload => panic!("unexpected payload: {:?}", payload),
+            }
+        }
+
+        let fee = core::GovernanceSetMessageFee {
+            fee: U256::from(1000u64),
+        };
+        assert!(matches!(
+            classify(Chain::Solana, GOVERNANCE, fee.to_bytes(Chain::All)).unwrap(),
+            Payload::SetMessageFee(_, action) if action == fee
+        ));
+
+        let expiry = core::GovernanceSetPostedVAAExpiry {
+            expiration_time: 3600,
+        };
+        assert!(matches!(
+            classify(Chain::Solana, GOVERNANCE, expiry.to_bytes(Chain::Solana)).unwrap(),
+            Payload::SetPostedVAAExpiry(_, action) if action == expiry
+        ));
+
+        let emitter_fee = core::GovernanceSetEmitterFee {
+            emitter:      [4u8; 32],
+            fee:          U256::from(5000u64),
+            fee_per_byte: U256::zero(),
+        };
+        assert!(matches!(
+            classify(Chain::Solana, GOVERNANCE, emitter_fee.to_bytes(Chain::Solana)).unwrap(),
+            Payload::SetEmitterFee(_, action) if action == emitter_fee
+        ));
+
+        // Unknown actions of a known module are rejected.
+        let mut payload = fee.to_bytes(Chain::All);
+        payload[32] = 9;
+        assert_eq!(
+            classify(Chain::Solana, GOVERNANCE, payload.clone()),
+            Err(WormholeError::InvalidGovernanceAction)
+        );
+
+        // The governance emitter only emits governance.
+        payload[..32].copy_from_slice(&[0u8; 32]);
+        assert_eq!(
+            classify(Chain::Solana, GOVERNANCE, payload.clone()),
+            Err(WormholeError::InvalidGovernanceModule)
+        );
+        assert_eq!(
+            classify(Chain::Solana, [9u8; 32], payload).unwrap(),
+            Payload::Unknown
+        );
+    }
+}
@@ -4,72 +4,114 @@
 //! supports, namely contract upgrades and chain registrations.
 
 use nom::combinator::verify;
-use nom::multi::fill;
 use nom::number::complete::u8;
-use nom::{
-    Finish,
-    IResult,
-};
+use nom::IResult;
+use alloc::vec::Vec;
 use primitive_types::U256;
 
 use crate::vaa::{
+    FromPayload,
     GovernanceAction,
     parse_chain,
+    parse_field,
     parse_fixed,
+    require_consumed,
     ShortUTFString,
 };
 use crate::{
     parse_fixed_utf8,
+    write_fixed_utf8,
     Chain,
     WormholeError,
 };
+#[cfg(feature = "borsh")]
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+#[cfg(feature = "borsh")]
+use crate::encoding::{
+    borsh_deserialize_u256,
+    borsh_serialize_u256,
+};
 
 /// Transfer is a message containing specifics detailing a token lock up on a sending chain. Chains
 /// that are attempting to initiate a transfer must lock up tokens in some manner, such as in a
 /// custody account or via burning, before emitting this message.
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transfer {
     /// Amount being transferred (big-endian uint256)
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::u256_decimal"))]
     pub amount: U256,
 
     /// Address of the token. Left-zero-padded if shorter than 32 bytes
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub token_address: [u8; 32],
 
     /// Chain ID of the token
     pub token_chain: Chain,
 
     /// Address of the recipient. Left-zero-padded if shorter than 32 bytes
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub to: [u8; 32],
 
     /// Chain ID of the recipient
     pub to_chain: Chain,
 
     /// Amount of tokens (big-endian uint256) that the user is willing to pay as relayer fee. Must be <= Amount.
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::u256_decimal"))]
     pub fee: U256,
 }
 
 impl Transfer {
+    /// Parse a Transfer payload, rejecting any trailing bytes as the token bridge contracts do.
     pub fn from_bytes<T: AsRef<[u8]>>(input: T) -> Result<Self, WormholeError> {
-        match parse_payload_transfer(input.as_ref()).finish() {
-            Ok(input) => Ok(input.1),
-            Err(e) => Err(WormholeError::ParseError(e.code as usize)),
-        }
+        let input = input.as_ref();
+        let (rest, transfer) = parse_payload_transfer(input)?;
+        require_consumed(input, rest)?;
+        Ok(transfer)
+    }
+
+    /// As `from_bytes`, but ignores any bytes following the payload.
+    pub fn from_bytes_lenient<T: AsRef<[u8]>>(input: T) -> Result<Self, WormholeError> {
+        parse_payload_transfer(input.as_ref()).map(|(_, transfer)| transfer)
+    }
+
+    /// Serialize to Wormhole wire format, including the payload ID.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut amount = [0u8; 32];
+        let mut fee = [0u8; 32];
+        self.amount.to_big_endian(&mut amount);
+        self.fee.to_big_endian(&mut fee);
+
+        let mut v = Vec::with_capacity(133);
+        v.push(0x1);
+        v.extend_from_slice(&amount);
+        v.extend_from_slice(&self.token_address);
+        v.extend_from_slice(&u16::from(self.token_chain).to_be_bytes());
+        v.extend_from_slice(&self.to);
+        v.extend_from_slice(&u16::from(self.to_chain).to_be_bytes());
+        v.extend_from_slice(&fee);
+        v
     }
 }
 
-fn parse_payload_transfer(input: &[u8]) -> IResult<&[u8], Transfer> {
-    // Parser Buffers.
-    let mut amount = [0u8; 32];
-    let mut fee = [0u8; 32];
+impl FromPayload for Transfer {
+    fn from_payload(payload: &[u8]) -> Result<Self, WormholeError> {
+        Self::from_bytes(payload)
+    }
+}
 
+fn parse_payload_transfer(input: &[u8]) -> Result<(&[u8], Transfer), WormholeError> {
     // Parse Payload.
-    let (i, _) = verify(u8, |&s| s == 0x1)(input)?;
-    let (i, _) = fill(u8, &mut amount)(i)?;
-    let (i, token_address) = parse_fixed(i)?;
-    let (i, token_chain) = parse_chain(i)?;
-    let (i, to) = parse_fixed(i)?;
-    let (i, to_chain) = parse_chain(i)?;
-    let (i, _) = fill(u8, &mut fee)(i)?;
+    let (i, _) = parse_field(input, input, "payload_id", verify(u8, |&s| s == 0x1))?;
+    let (i, amount): (_, [u8; 32]) = parse_field(input, i, "amount", parse_fixed)?;
+    let (i, token_address) = parse_field(input, i, "token_address", parse_fixed)?;
+    let (i, token_chain) = parse_field(input, i, "token_chain", parse_chain)?;
+    let (i, to) = parse_field(input, i, "to", parse_fixed)?;
+    let (i, to_chain) = parse_field(input, i, "to_chain", parse_chain)?;
+    let (i, fee): (_, [u8; 32]) = parse_field(input, i, "fee", parse_fixed)?;
 
     Ok((
         i,
@@ -85,8 +127,11 @@ fn parse_payload_transfer(input: &[u8]) -> IResult<&[u8], Transfer> {
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 pub struct AssetMeta {
     /// Address of the original token on the source chain.
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub token_address: [u8; 32],
 
     /// Source Chain ID.
@@ -103,22 +148,47 @@ pub struct AssetMeta {
 }
 
 impl AssetMeta {
+    /// Parse an AssetMeta payload, rejecting any trailing bytes as the token bridge contracts do.
     pub fn from_bytes<T: AsRef<[u8]>>(input: T) -> Result<Self, WormholeError> {
-        match parse_payload_asset_meta(input.as_ref()).finish() {
-            Ok(input) => Ok(input.1),
-            Err(e) => Err(WormholeError::ParseError(e.code as usize)),
-        }
+        let input = input.as_ref();
+        let (rest, meta) = parse_payload_asset_meta(input)?;
+        require_consumed(input, rest)?;
+        Ok(meta)
+    }
+
+    /// As `from_bytes`, but ignores any bytes following the payload.
+    pub fn from_bytes_lenient<T: AsRef<[u8]>>(input: T) -> Result<Self, WormholeError> {
+        parse_payload_asset_meta(input.as_ref()).map(|(_, meta)| meta)
+    }
+
+    /// Serialize to Wormhole wire format, including the payload ID. Symbol and name are truncated
+    /// to 32 bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::with_capacity(100);
+        v.push(0x2);
+        v.extend_from_slice(&self.token_address);
+        v.extend_from_slice(&u16::from(self.token_chain).to_be_bytes());
+        v.push(self.decimals);
+        v.extend_from_slice(&write_fixed_utf8::<32>(&self.symbol));
+        v.extend_from_slice(&write_fixed_utf8::<32>(&self.name));
+        v
+    }
+}
+
+impl FromPayload for AssetMeta {
+    fn from_payload(payload: &[u8]) -> Result<Self, WormholeError> {
+        Self::from_bytes(payload)
     }
 }
 
-fn parse_payload_asset_meta(input: &[u8]) -> IResult<&[u8], AssetMeta> {
+fn parse_payload_asset_meta(input: &[u8]) -> Result<(&[u8], AssetMeta), WormholeError> {
     // Parse Payload.
-    let (i, _) = verify(u8, |&s| s == 0x2)(input.as_ref())?;
-    let (i, token_address) = parse_fixed(i)?;
-    let (i, token_chain) = parse_chain(i)?;
-    let (i, decimals) = u8(i)?;
-    let (i, symbol): (_, [u8; 32]) = parse_fixed(i)?;
-    let (i, name): (_, [u8; 32]) = parse_fixed(i)?;
+    let (i, _) = parse_field(input, input, "payload_id", verify(u8, |&s| s == 0x2))?;
+    let (i, token_address) = parse_field(input, i, "token_address", parse_fixed)?;
+    let (i, token_chain) = parse_field(input, i, "token_chain", parse_chain)?;
+    let (i, decimals) = parse_field(input, i, "decimals", u8)?;
+    let (i, symbol): (_, [u8; 32]) = parse_field(input, i, "symbol", parse_fixed)?;
+    let (i, name): (_, [u8; 32]) = parse_field(input, i, "name", parse_fixed)?;
 
     // Name/Symbol should be UTF-8 strings, attempt to parse them by removing invalid bytes.
     let symbol = parse_fixed_utf8::<_, 32>(symbol).unwrap();
@@ -137,8 +207,11 @@ fn parse_payload_asset_meta(input: &[u8]) -> IResult<&[u8], AssetMeta> {
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 pub struct GovernanceRegisterChain {
     pub emitter:          Chain,
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub endpoint_address: [u8; 32],
 }
 
@@ -156,10 +229,18 @@ impl GovernanceAction for GovernanceRegisterChain {
             },
         ))
     }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&u16::from(self.emitter).to_be_bytes());
+        output.extend_from_slice(&self.endpoint_address);
+    }
 }
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "borsh", derive(borsh::BorshSerialize, borsh::BorshDeserialize))]
 pub struct GovernanceContractUpgrade {
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::hex_bytes"))]
     pub new_contract: [u8; 32],
 }
 
@@ -170,4 +251,164 @@ impl GovernanceAction for GovernanceContractUpgrade {
         let (i, new_contract) = parse_fixed(input)?;
         Ok((i, Self { new_contract }))
     }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.extend_from_slice(&self.new_contract);
+    }
+}
+
+// U256 has no borsh support of its own, so types carrying one implement borsh by hand, see
+// `crate::encoding`.
+#[cfg(feature = "borsh")]
+impl borsh::BorshSerialize for Transfer {
+    fn serialize<W: borsh::maybestd::io::Write>(
+        &self,
+        writer: &mut W,
+    ) -> borsh::maybestd::io::Result<()> {
+        borsh_serialize_u256(&self.amount, writer)?;
+        BorshSerialize::serialize(&self.token_address, writer)?;
+        BorshSerialize::serialize(&self.token_chain, writer)?;
+        BorshSerialize::serialize(&self.to, writer)?;
+        BorshSerialize::serialize(&self.to_chain, writer)?;
+        borsh_serialize_u256(&self.fee, writer)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl borsh::BorshDeserialize for Transfer {
+    fn deserialize(buf: &mut &[u8]) -> borsh::maybestd::io::Result<Self> {
+        Ok(Self {
+            amount: borsh_deserialize_u256(buf)?,
+            token_address: BorshDeserialize::deserialize(buf)?,
+            token_chain: BorshDeserialize::deserialize(buf)?,
+            to: BorshDeserialize::deserialize(buf)?,
+            to_chain: BorshDeserialize::deserialize(buf)?,
+            fee: borsh_deserialize_u256(buf)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_payload_round_trip() {
+        let transfer = Transfer {
+            amount:        U256::from(100u64),
+            token_address: [1u8; 32],
+            token_chain:   Chain::Ethereum,
+            to:            [2u8; 32],
+            to_chain:      Chain::Solana,
+            fee:           U256::from(1u64),
+        };
+        assert_eq!(transfer.to_bytes().len(), 133);
+        assert_eq!(Transfer::from_bytes(transfer.to_bytes()).unwrap(), transfer);
+
+        let meta = AssetMeta {
+            token_address: [3u8; 32],
+            token_chain:   Chain::Terra,
+            decimals:      8,
+            symbol:        "LUNA".to_string(),
+            name:          "Terra Luna".to_string(),
+        };
+        assert_eq!(meta.to_bytes().len(), 100);
+        assert_eq!(AssetMeta::from_bytes(meta.to_bytes()).unwrap(), meta);
+
+        let register = GovernanceRegisterChain {
+            emitter:          Chain::Polygon,
+            endpoint_address: [4u8; 32],
+        };
+        let (_, parsed) =
+            GovernanceRegisterChain::from_bytes(register.to_bytes(Chain::All), None).unwrap();
+        assert_eq!(parsed, register);
+    }
+
+    /// Valid payloads along with every truncation of them, trailing bytes, and a corrupted first
+    /// byte, which covers the payload ID as well as the governance module.
+    fn mutations(payload: &[u8]) -> Vec<Vec<u8>> {
+        let mut inputs: Vec<Vec<u8>> = (0..payload.len()).map(|n| payload[..n].to_vec()).collect();
+        for extra in [&[0u8][..], &[1, 2, 3]] {
+            inputs.push([payload, extra].concat());
+        }
+        let mut corrupt = payload.to_vec();
+        corrupt[0] ^= 0xff;
+        inputs.push(corrupt);
+        inputs
+    }
+
+    #[test]
+    fn test_rejects_malformed() {
+        let transfer = Transfer {
+            amount:        U256::from(100u64),
+            token_address: [1u8; 32],
+            token_chain:   Chain::Ethereum,
+            to:            [2u8; 32],
+            to_chain:      Chain::Solana,
+            fee:           U256::from(1u64),
+        };
+        for input in mutations(&transfer.to_bytes()) {
+            assert!(Transfer::from_bytes(&input).is_err());
+        }
+
+        let meta = AssetMeta {
+            token_address: [3u8; 32],
+            token_chain:   Chain::Terra,
+            decimals:      8,
+            symbol:        "LUNA".to_string(),
+            name:          "Terra Luna".to_string(),
+        };
+        for input in mutations(&meta.to_bytes()) {
+            assert!(AssetMeta::from_bytes(&input).is_err());
+        }
+
+        // Governance targetting Solana is only accepted when intact, governance targetting another
+        // chain never is, including all chains when Solana was asked for.
+        let register = GovernanceRegisterChain {
+            emitter:          Chain::Polygon,
+            endpoint_address: [4u8; 32],
+        };
+        let payload = register.to_bytes(Chain::Solana);
+        assert!(GovernanceRegisterChain::from_bytes(&payload, Some(Chain::Solana)).is_ok());
+        for input in mutations(&payload) {
+            assert!(GovernanceRegisterChain::from_bytes(&input, Some(Chain::Solana)).is_err());
+        }
+        for chain in [Chain::Terra, Chain::All] {
+            let payload = register.to_bytes(chain);
+            assert!(GovernanceRegisterChain::from_bytes(&payload, Some(Chain::Solana)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_trailing_bytes() {
+        let meta = AssetMeta {
+            token_address: [3u8; 32],
+            token_chain:   Chain::Terra,
+            decimals:      8,
+            symbol:        "LUNA".to_string(),
+            name:          "Terra Luna".to_string(),
+        };
+        let mut bytes = meta.to_bytes();
+        bytes.push(0);
+
+        assert_eq!(
+            AssetMeta::from_bytes(&bytes),
+            Err(WormholeError::TrailingBytes { offset: 100 })
+        );
+        assert_eq!(AssetMeta::from_bytes_lenient(&bytes).unwrap(), meta);
+
+        let upgrade = GovernanceContractUpgrade {
+            new_contract: [5u8; 32],
+        };
+        let mut bytes = upgrade.to_bytes(Chain::All);
+        bytes.extend_from_slice(&[1, 2]);
+        assert_eq!(
+            GovernanceContractUpgrade::from_bytes(&bytes, None),
+            Err(WormholeError::TrailingBytes { offset: 67 })
+        );
+        let (_, parsed) = GovernanceContractUpgrade::from_bytes_lenient(&bytes, None).unwrap();
+        assert_eq!(parsed, upgrade);
+    }
 }
@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wormhole_sdk::vaa::token::AssetMeta;
+
+fuzz_target!(|data: &[u8]| {
+    // Invalid UTF-8 is dropped from the symbol and name, so only the parsed form round trips.
+    if let Ok(meta) = AssetMeta::from_bytes(data) {
+        assert_eq!(AssetMeta::from_bytes(meta.to_bytes()).unwrap(), meta);
+    }
+});
@@ -0,0 +1,169 @@
+#![no_main]
+use std::fmt::Debug;
+
+use bridge::vaa::DeserializePayload;
+use libfuzzer_sys::fuzz_target;
+use wormhole_sdk::vaa::{
+    core,
+    nft,
+    token,
+    GovernanceAction,
+};
+use wormhole_sdk::Chain;
+
+/// Feed the same bytes to a wormhole-core parser and to the Solana parser for the same payload.
+/// Both map their result to the fields they share, and must agree on whether the input is valid
+/// and on what it contains.
+fn agree<T: PartialEq + Debug>(
+    data: &[u8],
+    core: impl FnOnce(&[u8]) -> Option<T>,
+    solana: impl FnOnce(&[u8]) -> Option<T>,
+) {
+    assert_eq!(core(data), solana(data));
+}
+
+/// Parse a governance action as the Solana contracts do, accepting actions for Solana or any chain.
+fn governance<A: GovernanceAction>(data: &[u8]) -> Option<A> {
+    A::from_bytes(data, None)
+        .ok()
+        .filter(|(header, _)| header.chains == Chain::Solana || header.chains == Chain::All)
+        .map(|(_, action)| action)
+}
+
+fn solana<P: DeserializePayload>(data: &[u8]) -> Option<P> {
+    P::deserialize(&mut &data[..]).ok()
+}
+
+fuzz_target!(|data: &[u8]| {
+    // Core Governance.
+    agree(
+        data,
+        |d| governance::<core::GovernanceContractUpgrade>(d).map(|a| a.new_contract),
+        |d| solana::<bridge::types::GovernancePayloadUpgrade>(d).map(|p| p.new_contract.to_bytes()),
+    );
+    agree(
+        data,
+        |d| {
+            governance::<core::GovernanceGuardianSetChange>(d)
+                .map(|a| (a.new_guardian_set_index, a.new_guardian_set))
+        },
+        |d| {
+            solana::<bridge::types::GovernancePayloadGuardianSetChange>(d)
+                .map(|p| (p.new_guardian_set_index, p.new_guardian_set))
+        },
+    );
+    agree(
+        data,
+        |d| governance::<core::GovernanceSetMessageFee>(d).map(|a| a.fee),
+        |d| solana::<bridge::types::GovernancePayloadSetMessageFee>(d).map(|p| p.fee),
+    );
+    agree(
+        data,
+        |d| governance::<core::GovernanceTransferFees>(d).map(|a| (a.amount, a.to)),
+        |d| solana::<bridge::types::GovernancePayloadTransferFees>(d).map(|p| (p.amount, p.to)),
+    );
+    agree(
+        data,
+        |d| governance::<core::GovernanceSetPostedVAAExpiry>(d).map(|a| a.expiration_time),
+        |d| {
+            solana::<bridge::types::GovernancePayloadSetPostedVAAExpiry>(d)
+                .map(|p| p.expiration_time)
+        },
+    );
+    agree(
+        data,
+        |d| governance::<core::GovernanceSetQuorum>(d).map(|a| (a.guardian_set_index, a.quorum)),
+        |d| {
+            solana::<bridge::types::GovernancePayloadSetQuorum>(d)
+                .map(|p| (p.guardian_set_index, p.quorum))
+        },
+    );
+    agree(
+        data,
+        |d| {
+            governance::<core::GovernanceSetEmitterFee>(d)
+                .map(|a| (a.emitter, a.fee, a.fee_per_byte))
+        },
+        |d| {
+            solana::<bridge::types::GovernancePayloadSetEmitterFee>(d)
+                .map(|p| (p.emitter.to_bytes(), p.fee, p.fee_per_byte))
+        },
+    );
+
+    // Token Bridge.
+    agree(
+        data,
+        |d| {
+            token::Transfer::from_bytes(d).ok().map(|t| {
+                let (token_chain, to_chain) = (u16::from(t.token_chain), u16::from(t.to_chain));
+                (t.amount, t.token_address, token_chain, t.to, to_chain, t.fee)
+            })
+        },
+        |d| {
+            solana::<token_bridge::messages::PayloadTransfer>(d)
+                .map(|p| (p.amount, p.token_address, p.token_chain, p.to, p.to_chain, p.fee))
+        },
+    );
+    agree(
+        data,
+        |d| {
+            token::AssetMeta::from_bytes(d)
+                .ok()
+                .map(|m| (m.token_address, u16::from(m.token_chain), m.decimals))
+        },
+        |d| {
+            solana::<token_bridge::messages::PayloadAssetMeta>(d)
+                .map(|p| (p.token_address, p.token_chain, p.decimals))
+        },
+    );
+    agree(
+        data,
+        |d| {
+            governance::<token::GovernanceRegisterChain>(d)
+                .map(|a| (u16::from(a.emitter), a.endpoint_address))
+        },
+        |d| {
+            solana::<token_bridge::messages::PayloadGovernanceRegisterChain>(d)
+                .map(|p| (p.chain, p.endpoint_address))
+        },
+    );
+    agree(
+        data,
+        |d| governance::<token::GovernanceContractUpgrade>(d).map(|a| a.new_contract),
+        |d| {
+            solana::<token_bridge::messages::GovernancePayloadUpgrade>(d)
+                .map(|p| p.new_contract.to_bytes())
+        },
+    );
+
+    // NFT Bridge.
+    agree(
+        data,
+        |d| {
+            governance::<nft::GovernanceRegisterChain>(d)
+                .map(|a| (u16::from(a.emitter), a.endpoint_address))
+        },
+        |d| {
+            solana::<nft_bridge::messages::PayloadGovernanceRegisterChain>(d)
+                .map(|p| (p.chain, p.endpoint_address))
+        },
+    );
+    agree(
+        data,
+        |d| governance::<nft::GovernanceContractUpgrade>(d).map(|a| a.new_contract),
+        |d| {
+            solana::<nft_bridge::messages::GovernancePayloadUpgrade>(d)
+                .map(|p| p.new_contract.to_bytes())
+        },
+    );
+
+    // The Solana NFT parser panics on a URI that is not valid UTF-8, which wormhole-core rejects,
+    // so it is only fed transfers wormhole-core accepts.
+    if let Ok(t) = nft::Transfer::from_bytes(data) {
+        let p = solana::<nft_bridge::messages::PayloadTransfer>(data).unwrap();
+        assert_eq!(
+            (t.nft_address, u16::from(t.nft_chain), t.token_id, t.uri, t.to, u16::from(t.to_chain)),
+            (p.token_address, p.token_chain, p.token_id, p.uri, p.to, p.to_chain),
+        );
+    }
+});
@@ -0,0 +1,36 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use sha3::{
+    Digest,
+    Keccak256,
+};
+use wormhole::state::ParsedVAA;
+use wormhole_sdk::VAA;
+
+fuzz_target!(|data: &[u8]| {
+    // The Terra parser indexes into the header without checking its length.
+    if data.len() < ParsedVAA::HEADER_LEN {
+        return;
+    }
+
+    let core = VAA::from_bytes(data);
+    let terra = ParsedVAA::deserialize(data);
+    assert_eq!(core.is_ok(), terra.is_ok());
+
+    if let (Ok(core), Ok(terra)) = (core, terra) {
+        assert_eq!(core.version, terra.version);
+        assert_eq!(core.guardian_set_index, terra.guardian_set_index);
+        assert_eq!(core.signatures.len(), terra.len_signers as usize);
+        assert_eq!(core.timestamp, terra.timestamp);
+        assert_eq!(core.nonce, terra.nonce);
+        assert_eq!(u16::from(core.emitter_chain), terra.emitter_chain);
+        assert_eq!(&core.emitter_address[..], &terra.emitter_address[..]);
+        assert_eq!(core.sequence, terra.sequence);
+        assert_eq!(core.consistency_level, terra.consistency_level);
+        assert_eq!(core.payload, terra.payload);
+
+        // Terra stores the hash guardians sign, the hash of the body hash.
+        let hash = core.digest().unwrap().hash;
+        assert_eq!(&Keccak256::digest(&hash)[..], &terra.hash[..]);
+    }
+});
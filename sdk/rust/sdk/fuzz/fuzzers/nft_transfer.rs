@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wormhole_sdk::vaa::nft::Transfer;
+
+fuzz_target!(|data: &[u8]| {
+    // Invalid UTF-8 is dropped from the symbol and name, so only the parsed form round trips.
+    if let Ok(transfer) = Transfer::from_bytes(data) {
+        assert_eq!(Transfer::from_bytes(transfer.to_bytes()).unwrap(), transfer);
+    }
+});
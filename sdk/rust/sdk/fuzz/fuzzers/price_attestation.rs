@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use pyth2wormhole::types::PriceAttestation;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(attestation) = PriceAttestation::deserialize(data) {
+        let parsed = PriceAttestation::deserialize(&attestation.serialize()[..]).unwrap();
+        assert_eq!(parsed, attestation);
+    }
+});
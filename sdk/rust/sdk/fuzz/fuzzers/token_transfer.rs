@@ -0,0 +1,10 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use wormhole_sdk::vaa::token::Transfer;
+
+fuzz_target!(|data: &[u8]| {
+    // Every field is fixed width, so anything that parses serializes back to the same bytes.
+    if let Ok(transfer) = Transfer::from_bytes(data) {
+        assert_eq!(transfer.to_bytes(), data);
+    }
+});
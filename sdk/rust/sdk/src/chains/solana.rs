@@ -1,40 +1,51 @@
 use borsh::BorshDeserialize;
 use solana_program::pubkey::Pubkey;
 use solana_program::account_info::AccountInfo;
-use solana_program::entrypoint::ProgramResult;
+use solana_program::instruction::Instruction;
 use solana_program::program::invoke_signed;
+use solana_program::program_error::ProgramError;
+use solana_program::rent::Rent;
+use solana_program::system_instruction;
+use solana_program::sysvar::Sysvar;
 use std::str::FromStr;
 
 // Export Bridge API
 pub use bridge::BridgeConfig;
 pub use bridge::BridgeData;
+pub use bridge::EmitterFeeData;
 pub use bridge::MessageData;
 pub use bridge::PostVAAData;
 pub use bridge::PostedVAAData;
 pub use bridge::VerifySignaturesData;
+pub use bridge::events;
 pub use bridge::instructions;
 pub use bridge::solitaire as bridge_entrypoint;
 pub use bridge::types::ConsistencyLevel;
 
+use wormhole_core::Chain;
+use wormhole_core::ForeignAddress;
 use wormhole_core::WormholeError;
 use wormhole_core::VAA;
 
-/// Export Core Mainnet Contract Address
-#[cfg(feature = "mainnet")]
-pub fn id() -> Pubkey {
-    Pubkey::from_str("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth").unwrap()
-}
+use crate::Network;
 
-/// Export Core Devnet Contract Address
-#[cfg(feature = "testnet")]
-pub fn id() -> Pubkey {
-    Pubkey::from_str("3u8hJUVTA4jH1wYAyUur7FFZVQ8H635K3tSHHF4ssjQ5").unwrap()
+#[cfg(feature = "client")]
+pub mod client;
+
+/// Export Core Contract Address for a Wormhole deployment.
+pub fn bridge_id(network: Network) -> Pubkey {
+    let id = match network {
+        Network::Mainnet => "worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth",
+        Network::Testnet => "3u8hJUVTA4jH1wYAyUur7FFZVQ8H635K3tSHHF4ssjQ5",
+        Network::Devnet => "Bridge1p5gheXUvJ6jGWGeCsgPKgnE3YgdGKRVCMY9o",
+    };
+    Pubkey::from_str(id).unwrap()
 }
 
-/// Export Local Tilt Devnet Contract Address
-#[cfg(feature = "devnet")]
+/// Export Core Contract Address for the network selected by feature flag.
+#[cfg(any(feature = "mainnet", feature = "testnet", feature = "devnet"))]
 pub fn id() -> Pubkey {
-    Pubkey::from_str("Bridge1p5gheXUvJ6jGWGeCsgPKgnE3YgdGKRVCMY9o").unwrap()
+    bridge_id(crate::NETWORK)
 }
 
 /// Derives the Wormhole configuration account address.
@@ -56,6 +67,43 @@ pub fn sequence(id: &Pubkey, emitter: &Pubkey) -> Pubkey {
     sequence
 }
 
+/// Derives the address of the fee governance has set for an emitter, which replaces the flat
+/// message fee in the config once it exists. Programs posting messages for such an emitter must
+/// pass this account to `post_message`.
+pub fn emitter_fee(id: &Pubkey, emitter: &Pubkey) -> Pubkey {
+    let (emitter_fee, _) = Pubkey::find_program_address(&[b"EmitterFee", &emitter.to_bytes()], id);
+    emitter_fee
+}
+
+/// Derives the message account an emitter overwrites with every unreliable message it posts.
+pub fn message_unreliable(id: &Pubkey, emitter: &Pubkey) -> Pubkey {
+    let (message, _) = Pubkey::find_program_address(
+        &[b"PostedMessageUnreliable", &emitter.to_bytes()],
+        id,
+    );
+    message
+}
+
+/// Derives the address of the account a VAA is posted to, from the hash of its body.
+pub fn posted_vaa(id: &Pubkey, hash: &[u8; 32]) -> Pubkey {
+    let (posted_vaa, _) = Pubkey::find_program_address(&[b"PostedVAA", hash], id);
+    posted_vaa
+}
+
+/// Derives the claim account for a VAA under `program_id`, following the scheme the Wormhole token
+/// and NFT bridges use to mark VAAs as processed. Each emitter and sequence pair maps to a single
+/// account, so a program that creates it when acting on a VAA can never act on the VAA twice.
+pub fn claim(program_id: &Pubkey, vaa: &PostedVAAData) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            &vaa.emitter_address,
+            &vaa.emitter_chain.to_be_bytes(),
+            &vaa.sequence.to_be_bytes(),
+        ],
+        program_id,
+    )
+}
+
 /// Derives the emitter address for a Solana contract, the emitter on Solana must be a signer, this
 /// function helps generate a PDA and bump seed so users can emit using a PDA as the emitter.
 pub fn emitter(id: &Pubkey) -> (Pubkey, Vec<&[u8]>, u8) {
@@ -77,9 +125,234 @@ pub fn read_vaa(vaa: &AccountInfo) -> Result<PostedVAAData, WormholeError> {
        .map_err(|_| WormholeError::DeserializeFailed)?)
 }
 
+/// Errors returned by `verify_posted_vaa`. These convert into the closest `ProgramError` so they
+/// can be returned directly from a program entrypoint.
+#[derive(Debug, PartialEq)]
+pub enum VerifyVAAError {
+    /// The account is not owned by the Wormhole program.
+    InvalidOwner,
+
+    /// The account does not hold a posted VAA.
+    InvalidData,
+
+    /// The account is not the one the Wormhole program posts this VAA to.
+    InvalidDerivation,
+
+    /// The VAA was not emitted by the expected emitter.
+    UnexpectedEmitter {
+        chain:   Chain,
+        address: ForeignAddress,
+    },
+}
+
+impl From<VerifyVAAError> for ProgramError {
+    fn from(other: VerifyVAAError) -> ProgramError {
+        match other {
+            VerifyVAAError::InvalidOwner => ProgramError::IllegalOwner,
+            VerifyVAAError::InvalidData => ProgramError::InvalidAccountData,
+            VerifyVAAError::InvalidDerivation => ProgramError::InvalidSeeds,
+            VerifyVAAError::UnexpectedEmitter { .. } => ProgramError::InvalidAccountData,
+        }
+    }
+}
+
+/// Read a posted VAA from an account, checking it was emitted by `emitter` on `emitter_chain`. The
+/// Wormhole program only posts a VAA once its signatures have been verified, but `read_vaa` will
+/// deserialize any account it is given. This checks the account is owned by the Wormhole program
+/// selected by feature flag, and is the account that VAA would be posted to.
+#[cfg(any(feature = "mainnet", feature = "testnet", feature = "devnet"))]
+pub fn verify_posted_vaa(
+    account: &AccountInfo,
+    emitter_chain: Chain,
+    emitter: &ForeignAddress,
+) -> Result<PostedVAAData, VerifyVAAError> {
+    verify_posted_vaa_with(&id(), account, emitter_chain, emitter)
+}
+
+/// As `verify_posted_vaa`, but for VAAs posted by the Wormhole program at `bridge_id`.
+pub fn verify_posted_vaa_with(
+    bridge_id: &Pubkey,
+    account: &AccountInfo,
+    emitter_chain: Chain,
+    emitter: &ForeignAddress,
+) -> Result<PostedVAAData, VerifyVAAError> {
+    if account.owner != bridge_id {
+        return Err(VerifyVAAError::InvalidOwner);
+    }
+
+    // Posted VAAs are prefixed with a magic value, which also keeps messages posted by the same
+    // program from being mistaken for VAAs.
+    if !account.data.borrow().starts_with(b"vaa") {
+        return Err(VerifyVAAError::InvalidData);
+    }
+
+    let vaa = read_vaa(account).map_err(|_| VerifyVAAError::InvalidData)?;
+
+    // The account is derived from the hash of the VAA body, which only matches if the contents
+    // are exactly those that were verified when it was posted.
+    let hash = VAA {
+        timestamp:         vaa.vaa_time,
+        nonce:             vaa.nonce,
+        emitter_chain:     vaa.emitter_chain.into(),
+        emitter_address:   vaa.emitter_address,
+        sequence:          vaa.sequence,
+        consistency_level: vaa.consistency_level,
+        payload:           vaa.payload.clone(),
+        ..VAA::default()
+    }
+    .digest()
+    .ok_or(VerifyVAAError::InvalidData)?
+    .hash;
+
+    if *account.key != posted_vaa(bridge_id, &hash) {
+        return Err(VerifyVAAError::InvalidDerivation);
+    }
+
+    if Chain::from(vaa.emitter_chain) != emitter_chain || vaa.emitter_address != *emitter {
+        return Err(VerifyVAAError::UnexpectedEmitter {
+            chain:   vaa.emitter_chain.into(),
+            address: vaa.emitter_address,
+        });
+    }
+
+    Ok(vaa)
+}
+
+/// Errors returned by `claim_vaa`. These convert into the closest `ProgramError` so they can be
+/// returned directly from a program entrypoint.
+#[derive(Debug, PartialEq)]
+pub enum ClaimError {
+    /// The claim account for the VAA was not among the accounts passed.
+    MissingClaim,
+
+    /// The VAA has already been claimed.
+    AlreadyClaimed,
+
+    /// Creating the claim account failed.
+    Program(ProgramError),
+}
+
+impl From<ProgramError> for ClaimError {
+    fn from(other: ProgramError) -> ClaimError {
+        ClaimError::Program(other)
+    }
+}
+
+impl From<ClaimError> for ProgramError {
+    fn from(other: ClaimError) -> ProgramError {
+        match other {
+            ClaimError::MissingClaim => ProgramError::NotEnoughAccountKeys,
+            ClaimError::AlreadyClaimed => ProgramError::AccountAlreadyInitialized,
+            ClaimError::Program(e) => e,
+        }
+    }
+}
+
+/// Mark a VAA as processed by creating its claim account, see `claim`. Fails if the VAA has been
+/// claimed before. The claim account is owned by `program_id` and funded by `payer`, and both must
+/// be among `accounts` along with the system program.
+pub fn claim_vaa(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    vaa: &PostedVAAData,
+    accounts: &[AccountInfo],
+) -> Result<(), ClaimError> {
+    let (claim, bump) = claim(program_id, vaa);
+    let claim = accounts
+        .iter()
+        .find(|item| *item.key == claim)
+        .ok_or(ClaimError::MissingClaim)?;
+
+    // Claims are never closed, so an account the program owns has been claimed. Anyone can send
+    // lamports to the claim address beforehand, so a balance on its own proves nothing.
+    if claim.owner == program_id || !claim.data_is_empty() {
+        return Err(ClaimError::AlreadyClaimed);
+    }
+
+    // The account holds a single `true` byte, matching the bridge's own `ClaimData`.
+    let rent = Rent::get()?;
+    let required = rent.minimum_balance(1);
+    let seeds: &[&[u8]] = &[
+        &vaa.emitter_address,
+        &vaa.emitter_chain.to_be_bytes(),
+        &vaa.sequence.to_be_bytes(),
+        &[bump],
+    ];
+
+    if claim.lamports() == 0 {
+        invoke_signed(
+            &system_instruction::create_account(payer, claim.key, required, 1, program_id),
+            accounts,
+            &[seeds],
+        )?;
+    } else {
+        // `create_account` fails on an account that already holds lamports, so a pre-funded claim
+        // is topped up to rent exemption and then allocated and assigned in place.
+        let shortfall = required.saturating_sub(claim.lamports());
+        if shortfall > 0 {
+            invoke_signed(
+                &system_instruction::transfer(payer, claim.key, shortfall),
+                accounts,
+                &[],
+            )?;
+        }
+        invoke_signed(&system_instruction::allocate(claim.key, 1), accounts, &[seeds])?;
+        invoke_signed(&system_instruction::assign(claim.key, program_id), accounts, &[seeds])?;
+    }
+
+    claim.data.borrow_mut()[0] = 1;
+    Ok(())
+}
+
+/// Errors returned by `post_message`. These convert into the closest `ProgramError` so they can
+/// be returned directly from a program entrypoint.
+#[derive(Debug, PartialEq)]
+pub enum PostMessageError {
+    /// The Wormhole config account was not among the accounts passed.
+    MissingConfig,
+
+    /// The Wormhole fee collector account was not among the accounts passed.
+    MissingFeeCollector,
+
+    /// The payer account was not among the accounts passed.
+    MissingPayer,
+
+    /// The Wormhole config account could not be deserialized.
+    InvalidConfig,
+
+    /// The payer cannot cover the Wormhole message fee.
+    InsufficientFunds { balance: u64, fee: u64 },
+
+    /// Paying the fee or invoking the Wormhole program failed.
+    Program(ProgramError),
+}
+
+impl From<ProgramError> for PostMessageError {
+    fn from(other: ProgramError) -> PostMessageError {
+        PostMessageError::Program(other)
+    }
+}
+
+impl From<PostMessageError> for ProgramError {
+    fn from(other: PostMessageError) -> ProgramError {
+        match other {
+            PostMessageError::MissingConfig => ProgramError::NotEnoughAccountKeys,
+            PostMessageError::MissingFeeCollector => ProgramError::NotEnoughAccountKeys,
+            PostMessageError::MissingPayer => ProgramError::NotEnoughAccountKeys,
+            PostMessageError::InvalidConfig => ProgramError::InvalidAccountData,
+            PostMessageError::InsufficientFunds { .. } => ProgramError::InsufficientFunds,
+            PostMessageError::Program(e) => e,
+        }
+    }
+}
+
 /// This helper method wraps the steps required to invoke Wormhole, it takes care of fee payment,
 /// emitter derivation, and function invocation. This will be the right thing to use if you need to
 /// simply emit a message in the most straight forward way possible.
+///
+/// Messages are emitted from the PDA derived from the `"emitter"` seed and posted to the Wormhole
+/// deployment selected by feature flag, see `post_message_with` to choose either at runtime.
+#[cfg(any(feature = "mainnet", feature = "testnet", feature = "devnet"))]
 pub fn post_message(
     program_id: Pubkey,
     payer: Pubkey,
@@ -89,46 +362,180 @@ pub fn post_message(
     seeds: Option<&[&[u8]]>,
     accounts: &[AccountInfo],
     nonce: u32,
-) -> ProgramResult {
+) -> Result<(), PostMessageError> {
+    post_message_with(
+        id(),
+        program_id,
+        &["emitter".as_bytes()],
+        payer,
+        message,
+        payload,
+        consistency,
+        seeds,
+        accounts,
+        nonce,
+    )
+}
+
+/// As `post_message`, but posts to the Wormhole program at `bridge_id`, emitting from the PDA of
+/// `program_id` derived from `emitter_seeds`. The bump seed is found and appended automatically.
+/// Any other signer seeds needed by the message account are passed in `seeds`.
+pub fn post_message_with(
+    bridge_id: Pubkey,
+    program_id: Pubkey,
+    emitter_seeds: &[&[u8]],
+    payer: Pubkey,
+    message: Pubkey,
+    payload: impl AsRef<[u8]>,
+    consistency: ConsistencyLevel,
+    seeds: Option<&[&[u8]]>,
+    accounts: &[AccountInfo],
+    nonce: u32,
+) -> Result<(), PostMessageError> {
     // Derive any necessary Pubkeys, derivation makes sure that we match the accounts the are being
     // provided by the user as well.
-    let id = id();
-    let fee_collector = fee_collector(&id);
-    let (emitter, mut emitter_seeds, bump) = emitter(&program_id);
+    let id = bridge_id;
+    let (emitter, bump) = Pubkey::find_program_address(emitter_seeds, &program_id);
+    let bump = &[bump];
+    let mut emitter_seeds = emitter_seeds.to_vec();
+    emitter_seeds.push(bump);
+
+    pay_fee(id, payer, &emitter, payload.as_ref().len(), accounts)?;
+
+    // Invoke the Wormhole post_message endpoint to create an on-chain message.
+    let mut instruction = instructions::post_message(
+        id,
+        payer,
+        emitter,
+        message,
+        nonce,
+        payload.as_ref().to_vec(),
+        consistency,
+    )
+    .map_err(|_| ProgramError::InvalidInstructionData)?;
+    push_emitter_fee(&mut instruction, &id, &emitter, accounts);
+
+    invoke_signed(
+        &instruction,
+        accounts,
+        &[&emitter_seeds, seeds.unwrap_or(&[])],
+    )?;
+
+    Ok(())
+}
+
+/// As `post_message`, but the message is only observed by guardians from the transaction log and
+/// is written to an account the emitter reuses for every message, see `message_unreliable`. Later
+/// messages must not be larger than the first.
+#[cfg(any(feature = "mainnet", feature = "testnet", feature = "devnet"))]
+pub fn post_message_unreliable(
+    program_id: Pubkey,
+    payer: Pubkey,
+    payload: impl AsRef<[u8]>,
+    consistency: ConsistencyLevel,
+    accounts: &[AccountInfo],
+    nonce: u32,
+) -> Result<(), PostMessageError> {
+    post_message_unreliable_with(
+        id(),
+        program_id,
+        &["emitter".as_bytes()],
+        payer,
+        payload,
+        consistency,
+        accounts,
+        nonce,
+    )
+}
+
+/// As `post_message_unreliable`, but posts to the Wormhole program at `bridge_id`, emitting from
+/// the PDA of `program_id` derived from `emitter_seeds`.
+pub fn post_message_unreliable_with(
+    bridge_id: Pubkey,
+    program_id: Pubkey,
+    emitter_seeds: &[&[u8]],
+    payer: Pubkey,
+    payload: impl AsRef<[u8]>,
+    consistency: ConsistencyLevel,
+    accounts: &[AccountInfo],
+    nonce: u32,
+) -> Result<(), PostMessageError> {
+    let id = bridge_id;
+    let (emitter, bump) = Pubkey::find_program_address(emitter_seeds, &program_id);
     let bump = &[bump];
+    let mut emitter_seeds = emitter_seeds.to_vec();
     emitter_seeds.push(bump);
 
-    // Filter for the Config AccountInfo so we can access its data.
-    let config = config(&id);
-    let config = accounts.iter().find(|item| *item.key == config).unwrap();
-    let config = read_config(config).unwrap();
+    pay_fee(id, payer, &emitter, payload.as_ref().len(), accounts)?;
+
+    let mut instruction = instructions::post_message_unreliable(
+        id,
+        payer,
+        emitter,
+        nonce,
+        payload.as_ref().to_vec(),
+        consistency,
+    )
+    .map_err(|_| ProgramError::InvalidInstructionData)?;
+    push_emitter_fee(&mut instruction, &id, &emitter, accounts);
+
+    invoke_signed(&instruction, accounts, &[&emitter_seeds])?;
+
+    Ok(())
+}
+
+/// Pay the Wormhole message fee from `payer`, which must be able to cover it. This is the fee
+/// governance has set for `emitter` if its account is among `accounts`, or the flat fee otherwise.
+fn pay_fee(
+    id: Pubkey,
+    payer: Pubkey,
+    emitter: &Pubkey,
+    len: usize,
+    accounts: &[AccountInfo],
+) -> Result<(), PostMessageError> {
+    let fee_collector = fee_collector(&id);
+
+    // Filter for the accounts we need to inspect before invoking Wormhole.
+    let find = |key: &Pubkey| accounts.iter().find(|item| item.key == key);
+    let config = find(&config(&id)).ok_or(PostMessageError::MissingConfig)?;
+    let config = read_config(config).map_err(|_| PostMessageError::InvalidConfig)?;
+    let fee = match find(&emitter_fee(&id, emitter)).filter(|item| *item.owner == id) {
+        Some(item) => EmitterFeeData::try_from_slice(&item.data.borrow())
+            .ok()
+            .and_then(|emitter_fee| emitter_fee.message_fee(len))
+            .ok_or(PostMessageError::InvalidConfig)?,
+        None => config.fee,
+    };
+    find(&fee_collector).ok_or(PostMessageError::MissingFeeCollector)?;
+    let balance = find(&payer).ok_or(PostMessageError::MissingPayer)?.lamports();
+    if balance < fee {
+        return Err(PostMessageError::InsufficientFunds { balance, fee });
+    }
 
     // Pay Fee to the Wormhole
     invoke_signed(
-        &solana_program::system_instruction::transfer(
+        &system_instruction::transfer(
             &payer,
             &fee_collector,
-            config.fee
+            fee
         ),
         accounts,
         &[],
     )?;
 
-    // Invoke the Wormhole post_message endpoint to create an on-chain message.
-    invoke_signed(
-        &instructions::post_message(
-            id,
-            payer,
-            emitter,
-            message,
-            nonce,
-            payload.as_ref().to_vec(),
-            consistency,
-        )
-        .unwrap(),
-        accounts,
-        &[&emitter_seeds, seeds.unwrap_or(&[])],
-    )?;
-
     Ok(())
 }
+
+/// The Wormhole program requires the fee account of an emitter once governance has set its fee,
+/// so pass it along when the caller did. Accounts a program invokes with must be among its own.
+fn push_emitter_fee(
+    instruction: &mut Instruction,
+    id: &Pubkey,
+    emitter: &Pubkey,
+    accounts: &[AccountInfo],
+) {
+    let emitter_fee = instructions::emitter_fee_account(*id, *emitter);
+    if accounts.iter().any(|item| *item.key == emitter_fee.pubkey) {
+        instruction.accounts.push(emitter_fee);
+    }
+}
@@ -0,0 +1,341 @@
+//! Off-chain helpers for building the transactions that interact with Wormhole on Solana.
+//!
+//! Posting a VAA happens in two stages. Signatures are first checked by the secp256k1 program in
+//! batches, each batch followed by a `verify_signatures` instruction that records the verified
+//! guardians in a signature set account. Once every batch has landed, `post_vaa` checks the
+//! signature set for quorum and writes the VAA to its posted VAA account.
+//!
+//! VAAs signed by small guardian sets can instead be verified and posted by a single transaction,
+//! see `verify_and_post_vaa_instructions`.
+
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+
+use wormhole_core::{
+    require,
+    GuardianAddress,
+    Signature,
+    VAARef,
+    WormholeError,
+};
+
+use super::{
+    instructions,
+    posted_vaa,
+    PostVAAData,
+    VerifyAndPostVAAData,
+    VerifySignaturesData,
+};
+
+/// Number of signatures verified per transaction. Each signature adds 96 bytes of secp256k1
+/// instruction data, seven keeps a transaction with both instructions under the 1232 byte limit.
+pub const SIGNATURES_PER_TRANSACTION: usize = 7;
+
+/// The instructions needed to post a VAA, along with the accounts they create.
+#[derive(Debug)]
+pub struct PostVAAInstructions {
+    /// Each entry is a single transaction verifying a batch of signatures. These must be signed by
+    /// the payer and the signature set, and can be submitted in any order.
+    pub verify_signatures: Vec<Vec<Instruction>>,
+
+    /// Posts the VAA, submitted after every signature verification transaction has landed.
+    pub post_vaa: Instruction,
+
+    /// The signature set account passed in, which records verified signatures.
+    pub signature_set: Pubkey,
+
+    /// The account the VAA will be posted to.
+    pub posted_vaa: Pubkey,
+}
+
+/// Build the instructions that post `vaa` to the Wormhole program at `bridge_id`.
+///
+/// `guardian_set` must be the guardian set the VAA was signed by, and `signature_set` a fresh
+/// keypair that will sign the verification transactions. Signatures are not checked here, that is
+/// left to the secp256k1 program, but signatures by guardians outside of the set are rejected.
+pub fn post_vaa_instructions(
+    bridge_id: Pubkey,
+    payer: Pubkey,
+    signature_set: Pubkey,
+    vaa: &[u8],
+    guardian_set: &[GuardianAddress],
+) -> Result<PostVAAInstructions, WormholeError> {
+    let vaa = VAARef::from_bytes(vaa)?;
+    let hash = vaa.digest().ok_or(WormholeError::DeserializeFailed)?.hash;
+    let signatures: Vec<_> = vaa.signatures().collect();
+
+    let mut verify_signatures = Vec::new();
+    for batch in signatures.chunks(SIGNATURES_PER_TRANSACTION) {
+        let (secp_ix, signers) = secp_instruction(batch, &hash, guardian_set, 0)?;
+        let verify_ix = instructions::verify_signatures(
+            bridge_id,
+            payer,
+            vaa.guardian_set_index,
+            signature_set,
+            VerifySignaturesData {
+                signers,
+                secp_instruction_index: None,
+            },
+        )
+        .map_err(|_| WormholeError::DeserializeFailed)?;
+
+        verify_signatures.push(vec![secp_ix, verify_ix]);
+    }
+
+    let post_vaa = instructions::post_vaa(bridge_id, payer, signature_set, post_vaa_data(&vaa));
+
+    Ok(PostVAAInstructions {
+        verify_signatures,
+        post_vaa,
+        signature_set,
+        posted_vaa: posted_vaa(&bridge_id, &hash),
+    })
+}
+
+/// Build a single transaction that verifies the signatures of `vaa` and posts it, skipping the
+/// signature set. Every signature and the VAA itself must fit into one transaction, so this is only
+/// usable for VAAs signed by small guardian sets such as those of devnet and testnet.
+pub fn verify_and_post_vaa_instructions(
+    bridge_id: Pubkey,
+    payer: Pubkey,
+    vaa: &[u8],
+    guardian_set: &[GuardianAddress],
+) -> Result<Vec<Instruction>, WormholeError> {
+    let vaa = VAARef::from_bytes(vaa)?;
+    let hash = vaa.digest().ok_or(WormholeError::DeserializeFailed)?.hash;
+    let signatures: Vec<_> = vaa.signatures().collect();
+
+    let (secp_ix, signers) = secp_instruction(&signatures, &hash, guardian_set, 0)?;
+    let post_ix = instructions::verify_and_post_vaa(
+        bridge_id,
+        payer,
+        VerifyAndPostVAAData {
+            vaa: post_vaa_data(&vaa),
+            signers,
+            secp_instruction_index: Some(0),
+        },
+    );
+
+    Ok(vec![secp_ix, post_ix])
+}
+
+/// Build a secp256k1 instruction verifying `signatures` over `hash`, to be placed at `index` in its
+/// transaction. Returns it along with each signing guardian paired with the position of its
+/// signature within it, as the bridge expects them.
+fn secp_instruction(
+    signatures: &[&Signature],
+    hash: &[u8; 32],
+    guardian_set: &[GuardianAddress],
+    index: u8,
+) -> Result<(Instruction, Vec<(u8, u8)>), WormholeError> {
+    // Offsets into the secp256k1 instruction data, laid out as the count, a table of offsets,
+    // then each signature with its guardian key, and finally the message.
+    let data_offset = 1 + signatures.len() * 11;
+    let message_offset = data_offset + signatures.len() * 85;
+
+    let mut secp_payload = Vec::with_capacity(message_offset + hash.len());
+    let mut signers = Vec::with_capacity(signatures.len());
+    secp_payload.push(signatures.len() as u8);
+
+    for (i, signature) in signatures.iter().enumerate() {
+        let guardian = signature[0];
+        require!(
+            (guardian as usize) < guardian_set.len(),
+            WormholeError::GuardianIndexOutOfRange(guardian)
+        );
+
+        let signature_offset = data_offset + 85 * i;
+        secp_payload.extend_from_slice(&(signature_offset as u16).to_le_bytes());
+        secp_payload.push(index);
+        secp_payload.extend_from_slice(&(signature_offset as u16 + 65).to_le_bytes());
+        secp_payload.push(index);
+        secp_payload.extend_from_slice(&(message_offset as u16).to_le_bytes());
+        secp_payload.extend_from_slice(&(hash.len() as u16).to_le_bytes());
+        secp_payload.push(index);
+        signers.push((guardian, i as u8));
+    }
+
+    for signature in signatures {
+        secp_payload.extend_from_slice(&signature[1..]);
+        secp_payload.extend_from_slice(&guardian_set[signature[0] as usize]);
+    }
+
+    secp_payload.extend_from_slice(hash);
+
+    let secp_ix = Instruction {
+        program_id: solana_program::secp256k1_program::id(),
+        data:       secp_payload,
+        accounts:   vec![],
+    };
+
+    Ok((secp_ix, signers))
+}
+
+fn post_vaa_data(vaa: &VAARef) -> PostVAAData {
+    PostVAAData {
+        version:            vaa.version,
+        guardian_set_index: vaa.guardian_set_index,
+        timestamp:          vaa.timestamp,
+        nonce:              vaa.nonce,
+        emitter_chain:      vaa.emitter_chain.into(),
+        emitter_address:    vaa.emitter_address,
+        sequence:           vaa.sequence,
+        consistency_level:  vaa.consistency_level,
+        payload:            vaa.payload.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use solana_program::message::Message;
+    use wormhole_core::{
+        guardian_address,
+        SecretKey,
+        VAABuilder,
+        VAA,
+    };
+
+    fn guardians(n: u8) -> Vec<SecretKey> {
+        (1..=n).map(|seed| SecretKey::parse(&[seed; 32]).unwrap()).collect()
+    }
+
+    fn signed_vaa(guardians: &[SecretKey]) -> VAA {
+        VAABuilder::new()
+            .timestamp(1000)
+            .nonce(1)
+            .emitter_chain(wormhole_core::Chain::Ethereum)
+            .emitter_address([1u8; 32])
+            .sequence(10)
+            .payload(vec![7u8; 128])
+            .sign(guardians)
+    }
+
+    /// The secp256k1 instruction data built for a batch of signatures by `verify_signatures_ix` in
+    /// the bridge's wasm bindings, which existing clients already submit.
+    fn wasm_secp_data(
+        signatures: &[Signature],
+        keys: &[GuardianAddress],
+        hash: &[u8; 32],
+    ) -> Vec<u8> {
+        let data_offset = 1 + signatures.len() * 11;
+        let message_offset = data_offset + signatures.len() * 85;
+
+        let mut data = vec![signatures.len() as u8];
+        for i in 0..signatures.len() {
+            data.extend_from_slice(&((data_offset + 85 * i) as u16).to_le_bytes());
+            data.push(0);
+            data.extend_from_slice(&((data_offset + 85 * i + 65) as u16).to_le_bytes());
+            data.push(0);
+            data.extend_from_slice(&(message_offset as u16).to_le_bytes());
+            data.extend_from_slice(&(hash.len() as u16).to_le_bytes());
+            data.push(0);
+        }
+        for signature in signatures {
+            data.extend_from_slice(&signature[1..]);
+            data.extend_from_slice(&keys[signature[0] as usize]);
+        }
+        data.extend_from_slice(hash);
+        data
+    }
+
+    #[test]
+    fn test_post_vaa_batches() {
+        let guardians = guardians(19);
+        let keys: Vec<GuardianAddress> = guardians.iter().map(guardian_address).collect();
+        let vaa = signed_vaa(&guardians);
+        let hash = vaa.digest().unwrap().hash;
+
+        let bridge_id = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let signature_set = Pubkey::new_unique();
+        let ixs = post_vaa_instructions(bridge_id, payer, signature_set, &vaa.to_bytes(), &keys)
+            .unwrap();
+
+        // 19 signatures are split into batches of 7, 7 and 5.
+        assert_eq!(ixs.verify_signatures.len(), 3);
+        for (batch, (tx, signatures)) in ixs
+            .verify_signatures
+            .iter()
+            .zip(vaa.signatures.chunks(SIGNATURES_PER_TRANSACTION))
+            .enumerate()
+        {
+            assert_eq!(tx.len(), 2);
+            assert_eq!(tx[0].program_id, solana_program::secp256k1_program::id());
+            assert_eq!(tx[0].data, wasm_secp_data(signatures, &keys, &hash));
+
+            // Signers pair the guardian index with the signature's position within the batch.
+            let signers: Vec<(u8, u8)> = (0..signatures.len())
+                .map(|i| ((batch * SIGNATURES_PER_TRANSACTION + i) as u8, i as u8))
+                .collect();
+            let verify_ix = instructions::verify_signatures(
+                bridge_id,
+                payer,
+                vaa.guardian_set_index,
+                signature_set,
+                VerifySignaturesData {
+                    signers,
+                    secp_instruction_index: None,
+                },
+            )
+            .unwrap();
+            assert_eq!(tx[1], verify_ix);
+
+            // Signed by the payer and the signature set, which the message header accounts for.
+            let message = Message::new(tx, Some(&payer));
+            let signers = message.header.num_required_signatures as usize;
+            assert_eq!(signers, 2);
+            let size = 1 + 64 * signers + message.serialize().len();
+            assert!(size <= 1232, "batch {} is {} bytes", batch, size);
+        }
+
+        assert_eq!(ixs.signature_set, signature_set);
+        assert_eq!(ixs.posted_vaa, posted_vaa(&bridge_id, &hash));
+    }
+
+    #[test]
+    fn test_verify_and_post_vaa() {
+        let guardians = guardians(3);
+        let keys: Vec<GuardianAddress> = guardians.iter().map(guardian_address).collect();
+        let vaa = signed_vaa(&guardians);
+        let bytes = vaa.to_bytes();
+        let hash = vaa.digest().unwrap().hash;
+
+        let bridge_id = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let ixs = verify_and_post_vaa_instructions(bridge_id, payer, &bytes, &keys).unwrap();
+
+        assert_eq!(ixs.len(), 2);
+        assert_eq!(ixs[0].data, wasm_secp_data(&vaa.signatures, &keys, &hash));
+        assert_eq!(
+            ixs[1],
+            instructions::verify_and_post_vaa(
+                bridge_id,
+                payer,
+                VerifyAndPostVAAData {
+                    vaa:                    post_vaa_data(&VAARef::from_bytes(&bytes).unwrap()),
+                    signers:                vec![(0, 0), (1, 1), (2, 2)],
+                    secp_instruction_index: Some(0),
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn test_guardian_index_out_of_range() {
+        let guardians = guardians(3);
+        let keys: Vec<GuardianAddress> = guardians[..2].iter().map(guardian_address).collect();
+        let vaa = signed_vaa(&guardians).to_bytes();
+        let payer = Pubkey::new_unique();
+
+        assert_eq!(
+            post_vaa_instructions(Pubkey::new_unique(), payer, Pubkey::new_unique(), &vaa, &keys)
+                .unwrap_err(),
+            WormholeError::GuardianIndexOutOfRange(2)
+        );
+        assert_eq!(
+            verify_and_post_vaa_instructions(Pubkey::new_unique(), payer, &vaa, &keys).unwrap_err(),
+            WormholeError::GuardianIndexOutOfRange(2)
+        );
+    }
+}
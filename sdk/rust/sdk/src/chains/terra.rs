@@ -5,7 +5,9 @@ use cosmwasm_std::{
     CosmosMsg,
     DepsMut,
     Env,
+    QuerierWrapper,
     QueryRequest,
+    StdError,
     StdResult,
     WasmMsg,
     WasmQuery,
@@ -14,36 +16,58 @@ use serde::Serialize;
 
 use wormhole::msg::{
     ExecuteMsg,
+    GetAddressHexResponse,
+    GetStateResponse,
+    GuardianSetInfoResponse,
     QueryMsg,
 };
 use wormhole::state::ParsedVAA;
+use wormhole_core::FromPayload;
 
-/// Export Core Mainnet Contract Address
-#[cfg(feature = "mainnet")]
-pub fn id() -> Addr {
-    Addr::unchecked("terra1dq03ugtd40zu9hcgdzrsq6z2z4hwhc9tqk2uy5")
+use crate::Network;
+
+/// Export Core Contract Address for a Wormhole deployment.
+pub fn bridge_id(network: Network) -> Addr {
+    match network {
+        Network::Mainnet => Addr::unchecked("terra1dq03ugtd40zu9hcgdzrsq6z2z4hwhc9tqk2uy5"),
+        Network::Testnet => Addr::unchecked("terra1pd65m0q9tl3v8znnz5f5ltsfegyzah7g42cx5v"),
+        Network::Devnet => Addr::unchecked("terra18vd8fpwxzck93qlwghaj6arh4p7c5n896xzem5"),
+    }
 }
 
-/// Export Core Devnet Contract Address
-#[cfg(feature = "testnet")]
+/// Export Core Contract Address for the network selected by feature flag.
+#[cfg(any(feature = "mainnet", feature = "testnet", feature = "devnet"))]
 pub fn id() -> Addr {
-    Addr::unchecked("terra1pd65m0q9tl3v8znnz5f5ltsfegyzah7g42cx5v")
+    bridge_id(crate::NETWORK)
 }
 
-/// Export Core Devnet Contract Address
-#[cfg(feature = "devnet")]
-pub fn id() -> Addr {
-    Addr::unchecked("terra1pd65m0q9tl3v8znnz5f5ltsfegyzah7g42cx5v")
+/// Post a message through the Wormhole contract of the network selected by feature flag. The
+/// message fee is queried from the contract and attached to the message.
+#[cfg(any(feature = "mainnet", feature = "testnet", feature = "devnet"))]
+pub fn post_message<T>(querier: &QuerierWrapper, nonce: u32, message: &T) -> StdResult<CosmosMsg>
+where
+    T: Serialize,
+    T: ?Sized,
+{
+    post_message_with(querier, &id(), nonce, message)
 }
 
-pub fn post_message<T>(nonce: u32, message: &T) -> StdResult<CosmosMsg>
+/// Post a message through the Wormhole contract at `bridge_id`, attaching the message fee.
+pub fn post_message_with<T>(
+    querier: &QuerierWrapper,
+    bridge_id: &Addr,
+    nonce: u32,
+    message: &T,
+) -> StdResult<CosmosMsg>
 where
     T: Serialize,
     T: ?Sized,
 {
+    let fee = query_state(querier, bridge_id)?.fee;
+    let funds = if fee.amount.is_zero() { vec![] } else { vec![fee] };
     Ok(CosmosMsg::Wasm(WasmMsg::Execute {
-        contract_addr: id().to_string(),
-        funds:         vec![],
+        contract_addr: bridge_id.to_string(),
+        funds,
         msg:           to_binary(&ExecuteMsg::PostMessage {
             message: to_binary(message)?,
             nonce,
@@ -51,14 +75,25 @@ where
     }))
 }
 
-/// Parse a VAA using the Wormhole contract Query interface.
+/// Parse a VAA using the Query interface of the Wormhole contract selected by feature flag.
+#[cfg(any(feature = "mainnet", feature = "testnet", feature = "devnet"))]
 pub fn parse_vaa(
     deps: DepsMut,
     env: Env,
     data: &Binary,
+) -> StdResult<ParsedVAA> {
+    parse_vaa_with(&id(), deps, env, data)
+}
+
+/// Parse a VAA using the Query interface of the Wormhole contract at `bridge_id`.
+pub fn parse_vaa_with(
+    bridge_id: &Addr,
+    deps: DepsMut,
+    env: Env,
+    data: &Binary,
 ) -> StdResult<ParsedVAA> {
     let vaa: ParsedVAA = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-        contract_addr: id().to_string(),
+        contract_addr: bridge_id.to_string(),
         msg:           to_binary(&QueryMsg::VerifyVAA {
             vaa: data.clone(),
             block_time: env.block.time.seconds(),
@@ -66,3 +101,60 @@ pub fn parse_vaa(
     }))?;
     Ok(vaa)
 }
+
+/// Verify a VAA with the Wormhole contract selected by feature flag, then parse its payload. The
+/// VAA must be valid at the current block time.
+#[cfg(any(feature = "mainnet", feature = "testnet", feature = "devnet"))]
+pub fn parse_vaa_payload<T: FromPayload>(
+    querier: &QuerierWrapper,
+    env: &Env,
+    data: &Binary,
+) -> StdResult<(ParsedVAA, T)> {
+    parse_vaa_payload_with(querier, &id(), env, data)
+}
+
+/// Verify a VAA with the Wormhole contract at `bridge_id`, then parse its payload.
+pub fn parse_vaa_payload_with<T: FromPayload>(
+    querier: &QuerierWrapper,
+    bridge_id: &Addr,
+    env: &Env,
+    data: &Binary,
+) -> StdResult<(ParsedVAA, T)> {
+    let vaa: ParsedVAA = querier.query_wasm_smart(
+        bridge_id,
+        &QueryMsg::VerifyVAA {
+            vaa:        data.clone(),
+            block_time: env.block.time.seconds(),
+        },
+    )?;
+    let payload = T::from_payload(&vaa.payload).map_err(|e| StdError::parse_err("payload", e))?;
+    Ok((vaa, payload))
+}
+
+/// Query the current guardian set of the Wormhole contract at `bridge_id`.
+pub fn query_guardian_set_info(
+    querier: &QuerierWrapper,
+    bridge_id: &Addr,
+) -> StdResult<GuardianSetInfoResponse> {
+    querier.query_wasm_smart(bridge_id, &QueryMsg::GuardianSetInfo {})
+}
+
+/// Query the state of the Wormhole contract at `bridge_id`, currently the message fee.
+pub fn query_state(querier: &QuerierWrapper, bridge_id: &Addr) -> StdResult<GetStateResponse> {
+    querier.query_wasm_smart(bridge_id, &QueryMsg::GetState {})
+}
+
+/// Query the hex encoded 32 byte Wormhole representation of a Terra address, as used for emitter
+/// and recipient addresses.
+pub fn query_address_hex(
+    querier: &QuerierWrapper,
+    bridge_id: &Addr,
+    address: &str,
+) -> StdResult<GetAddressHexResponse> {
+    querier.query_wasm_smart(
+        bridge_id,
+        &QueryMsg::QueryAddressHex {
+            address: address.to_string(),
+        },
+    )
+}
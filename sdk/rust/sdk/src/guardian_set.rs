@@ -0,0 +1,405 @@
+//! Off-chain tracking of Wormhole guardian sets, for services that verify VAAs outside of the
+//! contracts. A `GuardianSetStore` follows guardian set changes the same way the contracts do, and
+//! caches the guardian addresses recovered from signatures so that VAAs seen more than once, or
+//! re-signed with a different subset of guardians, are cheap to verify.
+
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    VecDeque,
+};
+use std::convert::Infallible;
+
+use wormhole_core::vaa::core::GovernanceGuardianSetChange;
+use wormhole_core::{
+    recover_signer,
+    Chain,
+    ForeignAddress,
+    GovernanceAction,
+    GuardianAddress,
+    Signature,
+    WormholeError,
+    VAA,
+};
+
+/// Number of VAA digests whose recovered signers are kept by default.
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
+/// A guardian set, tracked with the same fields as the contracts' `GuardianSetData`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuardianSet {
+    /// Index representing an incrementing version number for this guardian set.
+    pub index: u32,
+
+    /// ETH style addresses of the guardians.
+    pub keys: Vec<GuardianAddress>,
+
+    /// Timestamp representing the time this guardian set became active.
+    pub creation_time: u32,
+
+    /// Expiration time after which VAAs signed by this set are no longer valid, zero while the
+    /// set is current.
+    pub expiration_time: u32,
+}
+
+impl GuardianSet {
+    /// Whether VAAs signed by this set are accepted at `now`, sets remain valid until the end of
+    /// the second they expire in.
+    pub fn is_active(&self, now: u32) -> bool {
+        self.expiration_time == 0 || now <= self.expiration_time
+    }
+}
+
+/// Storage for the guardian sets known to a `GuardianSetStore`, so a service can resume from the
+/// sets it has already seen rather than from its initial configuration.
+pub trait GuardianSetPersistence {
+    type Error;
+
+    /// Load every guardian set previously saved.
+    fn load(&mut self) -> Result<Vec<GuardianSet>, Self::Error>;
+
+    /// Save a guardian set, replacing any set previously saved with the same index.
+    fn save(&mut self, set: &GuardianSet) -> Result<(), Self::Error>;
+}
+
+/// Keeps guardian sets in memory only.
+impl GuardianSetPersistence for () {
+    type Error = Infallible;
+
+    fn load(&mut self) -> Result<Vec<GuardianSet>, Infallible> {
+        Ok(Vec::new())
+    }
+
+    fn save(&mut self, _: &GuardianSet) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// Errors returned by `GuardianSetStore`.
+#[derive(Debug, PartialEq)]
+pub enum GuardianSetError<E> {
+    /// The VAA was signed by a guardian set the store does not know about.
+    UnknownGuardianSet(u32),
+
+    /// The VAA was signed by a guardian set that has expired.
+    GuardianSetExpired(u32),
+
+    /// A guardian set change was not emitted by the governance emitter.
+    InvalidGovernanceEmitter,
+
+    /// A guardian set change was not signed by the current set, or does not replace it with the
+    /// set at the next index.
+    InvalidGuardianSetUpgrade,
+
+    /// The VAA failed to parse or verify.
+    Wormhole(WormholeError),
+
+    /// Loading or saving guardian sets failed.
+    Persistence(E),
+}
+
+impl<E> From<WormholeError> for GuardianSetError<E> {
+    fn from(other: WormholeError) -> GuardianSetError<E> {
+        GuardianSetError::Wormhole(other)
+    }
+}
+
+/// Tracks guardian sets and verifies VAAs against them, applying the rotation rules the contracts
+/// enforce: a set is replaced only by a governance VAA it signed itself introducing the set at the
+/// next index, after which it remains valid for a grace period before expiring.
+pub struct GuardianSetStore<P: GuardianSetPersistence = ()> {
+    persistence:        P,
+    sets:               BTreeMap<u32, GuardianSet>,
+    governance_chain:   Chain,
+    governance_emitter: ForeignAddress,
+    expiration:         u32,
+    signers:            HashMap<[u8; 32], Vec<(Signature, GuardianAddress)>>,
+    signers_order:      VecDeque<[u8; 32]>,
+    cache_capacity:     usize,
+}
+
+impl<P: GuardianSetPersistence> GuardianSetStore<P> {
+    /// Create a store holding the guardian sets saved in `persistence`. Guardian set changes are
+    /// only accepted from `governance_emitter` on `governance_chain`, and replaced sets expire
+    /// `expiration` seconds after the VAA that replaced them, as configured on the contracts.
+    pub fn new(
+        mut persistence: P,
+        governance_chain: Chain,
+        governance_emitter: ForeignAddress,
+        expiration: u32,
+    ) -> Result<Self, GuardianSetError<P::Error>> {
+        let sets = persistence
+            .load()
+            .map_err(GuardianSetError::Persistence)?
+            .into_iter()
+            .map(|set| (set.index, set))
+            .collect();
+
+        Ok(GuardianSetStore {
+            persistence,
+            sets,
+            governance_chain,
+            governance_emitter,
+            expiration,
+            signers: HashMap::new(),
+            signers_order: VecDeque::new(),
+            cache_capacity: DEFAULT_CACHE_CAPACITY,
+        })
+    }
+
+    /// Set the number of VAA digests whose recovered signers are cached, evicting the oldest
+    /// digests first.
+    pub fn with_cache_capacity(mut self, cache_capacity: usize) -> Self {
+        self.cache_capacity = cache_capacity;
+        self
+    }
+
+    /// The guardian set with the highest index.
+    pub fn current(&self) -> Option<&GuardianSet> {
+        self.sets.values().next_back()
+    }
+
+    /// The guardian set at `index`, if known.
+    pub fn get(&self, index: u32) -> Option<&GuardianSet> {
+        self.sets.get(&index)
+    }
+
+    /// Add a guardian set without a governance VAA, such as the initial set of a deployment.
+    pub fn insert(&mut self, set: GuardianSet) -> Result<(), GuardianSetError<P::Error>> {
+        self.persistence.save(&set).map_err(GuardianSetError::Persistence)?;
+        self.sets.insert(set.index, set);
+        Ok(())
+    }
+
+    /// Verify the signatures on a VAA against the guardian set that signed it, which must be
+    /// active at `now`.
+    pub fn verify(&mut self, vaa: &VAA, now: u32) -> Result<(), GuardianSetError<P::Error>> {
+        let set = self
+            .sets
+            .get(&vaa.guardian_set_index)
+            .ok_or(GuardianSetError::UnknownGuardianSet(vaa.guardian_set_index))?;
+
+        if !set.is_active(now) {
+            return Err(GuardianSetError::GuardianSetExpired(set.index));
+        }
+
+        let hash = vaa.digest().ok_or(WormholeError::DeserializeFailed)?.hash;
+        let signers = cached_signers(
+            &mut self.signers,
+            &mut self.signers_order,
+            self.cache_capacity,
+            hash,
+            &vaa.signatures,
+        );
+
+        vaa.verify_signers(&signers, &set.keys)?;
+        Ok(())
+    }
+
+    /// Verify a guardian set change VAA and apply it, returning the new guardian set. The set it
+    /// replaces expires after the configured expiration period.
+    pub fn apply(
+        &mut self,
+        vaa: &VAA,
+        now: u32,
+    ) -> Result<&GuardianSet, GuardianSetError<P::Error>> {
+        if vaa.emitter_chain != self.governance_chain
+            || vaa.emitter_address != self.governance_emitter
+        {
+            return Err(GuardianSetError::InvalidGovernanceEmitter);
+        }
+
+        let (_, change) = GovernanceGuardianSetChange::from_bytes(&vaa.payload, None)?;
+
+        // Enforce single increments, signed by the set being replaced.
+        let current = self.current().ok_or(GuardianSetError::InvalidGuardianSetUpgrade)?.index;
+        if vaa.guardian_set_index != current
+            || Some(change.new_guardian_set_index) != current.checked_add(1)
+        {
+            return Err(GuardianSetError::InvalidGuardianSetUpgrade);
+        }
+
+        self.verify(vaa, now)?;
+
+        let mut old = self.sets[&current].clone();
+        old.expiration_time = vaa.timestamp.saturating_add(self.expiration);
+        let new = GuardianSet {
+            index:           change.new_guardian_set_index,
+            keys:            change.new_guardian_set,
+            creation_time:   vaa.timestamp,
+            expiration_time: 0,
+        };
+
+        self.insert(old)?;
+        self.insert(new)?;
+        Ok(&self.sets[&change.new_guardian_set_index])
+    }
+}
+
+/// Look up the addresses recovered from each signature of the VAA with the given digest,
+/// recovering and caching any not seen before. Signatures that fail to recover are left out, and
+/// are reported by `VAA::verify_signers`.
+fn cached_signers(
+    cache: &mut HashMap<[u8; 32], Vec<(Signature, GuardianAddress)>>,
+    order: &mut VecDeque<[u8; 32]>,
+    capacity: usize,
+    hash: [u8; 32],
+    signatures: &[Signature],
+) -> Vec<GuardianAddress> {
+    if !cache.contains_key(&hash) {
+        while order.len() >= capacity.max(1) {
+            if let Some(oldest) = order.pop_front() {
+                cache.remove(&oldest);
+            }
+        }
+        order.push_back(hash);
+    }
+
+    let known = cache.entry(hash).or_default();
+    let mut signers = Vec::with_capacity(signatures.len());
+    for signature in signatures {
+        let cached = known.iter().find(|(s, _)| s == signature).map(|(_, a)| *a);
+        match cached.or_else(|| recover_signer(&hash, signature)) {
+            Some(address) => {
+                if cached.is_none() {
+                    known.push((*signature, address));
+                }
+                signers.push(address);
+            }
+            None => break,
+        }
+    }
+
+    signers
+}
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use wormhole_core::{
+        guardian_address,
+        SecretKey,
+        VAABuilder,
+    };
+
+    const GOVERNANCE: ForeignAddress = {
+        let mut address = [0u8; 32];
+        address[31] = 4;
+        address
+    };
+
+    fn guardian(seed: u8) -> SecretKey {
+        SecretKey::parse(&[seed; 32]).unwrap()
+    }
+
+    fn store(keys: Vec<GuardianAddress>) -> GuardianSetStore {
+        let mut store = GuardianSetStore::new((), Chain::Solana, GOVERNANCE, 100).unwrap();
+        store
+            .insert(GuardianSet {
+                index:           0,
+                keys,
+                creation_time:   0,
+                expiration_time: 0,
+            })
+            .unwrap();
+        store
+    }
+
+    fn guardian_set_change(index: u32, signer: &SecretKey, keys: Vec<GuardianAddress>) -> VAA {
+        let change = GovernanceGuardianSetChange {
+            new_guardian_set_index: index + 1,
+            new_guardian_set:       keys,
+        };
+
+        VAABuilder::new()
+            .guardian_set_index(index)
+            .timestamp(1000)
+            .emitter_chain(Chain::Solana)
+            .emitter_address(GOVERNANCE)
+            .payload(change.to_bytes(Chain::All))
+            .sign(&[*signer])
+    }
+
+    #[test]
+    fn test_verify_cached() {
+        let mut store = store(vec![guardian_address(&guardian(1))]);
+        let vaa = VAABuilder::new().timestamp(10).payload(vec![1]).sign(&[guardian(1)]);
+
+        // The second verification is served from the cache, and must agree with the first.
+        assert_eq!(store.verify(&vaa, 10), Ok(()));
+        assert_eq!(store.signers.len(), 1);
+        assert_eq!(store.verify(&vaa, 10), Ok(()));
+
+        // A cached signer does not make a different signature valid.
+        let mut forged = vaa.clone();
+        forged.signatures = VAABuilder::new().payload(vec![2]).sign(&[guardian(1)]).signatures;
+        assert_eq!(
+            store.verify(&forged, 10),
+            Err(GuardianSetError::Wormhole(WormholeError::SignatureMismatch(0)))
+        );
+
+        let unknown = VAABuilder::new().guardian_set_index(1).sign(&[guardian(1)]);
+        assert_eq!(
+            store.verify(&unknown, 10),
+            Err(GuardianSetError::UnknownGuardianSet(1))
+        );
+    }
+
+    #[test]
+    fn test_cache_eviction() {
+        let mut store = store(vec![guardian_address(&guardian(1))]).with_cache_capacity(2);
+        for payload in 0..4u8 {
+            let vaa = VAABuilder::new().payload(vec![payload]).sign(&[guardian(1)]);
+            assert_eq!(store.verify(&vaa, 0), Ok(()));
+        }
+        assert_eq!(store.signers.len(), 2);
+        assert_eq!(store.signers_order.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_guardian_set_change() {
+        let (old, new) = (guardian(1), guardian(2));
+        let mut store = store(vec![guardian_address(&old)]);
+        let signed_by_old = VAABuilder::new().timestamp(1000).sign(&[old]);
+
+        // Changes must come from the governance emitter.
+        let mut vaa = guardian_set_change(0, &old, vec![guardian_address(&new)]);
+        vaa.emitter_address = [1u8; 32];
+        assert_eq!(
+            store.apply(&vaa, 1000),
+            Err(GuardianSetError::InvalidGovernanceEmitter)
+        );
+
+        // And must be signed by the current set, introducing the next one.
+        let vaa = guardian_set_change(0, &new, vec![guardian_address(&new)]);
+        assert_eq!(
+            store.apply(&vaa, 1000),
+            Err(GuardianSetError::Wormhole(WormholeError::SignatureMismatch(0)))
+        );
+        let vaa = guardian_set_change(1, &old, vec![guardian_address(&new)]);
+        assert_eq!(
+            store.apply(&vaa, 1000),
+            Err(GuardianSetError::InvalidGuardianSetUpgrade)
+        );
+
+        let vaa = guardian_set_change(0, &old, vec![guardian_address(&new)]);
+        let set = store.apply(&vaa, 1000).unwrap().clone();
+        assert_eq!(set.index, 1);
+        assert_eq!(set.keys, vec![guardian_address(&new)]);
+        assert_eq!(store.current(), Some(&set));
+
+        // The change cannot be replayed.
+        assert_eq!(
+            store.apply(&vaa, 1000),
+            Err(GuardianSetError::InvalidGuardianSetUpgrade)
+        );
+
+        // The old set remains valid until it expires.
+        assert_eq!(store.get(0).unwrap().expiration_time, 1100);
+        assert_eq!(store.verify(&signed_by_old, 1100), Ok(()));
+        assert_eq!(
+            store.verify(&signed_by_old, 1101),
+            Err(GuardianSetError::GuardianSetExpired(0))
+        );
+    }
+}
@@ -16,6 +16,12 @@
 //! at the root of this package.
 
 pub mod chains;
+pub mod guardian_set;
+pub mod network;
 
+pub use network::*;
 pub use wormhole_core::*;
+
+// Empty when no runtime feature is enabled.
+#[allow(unused_imports)]
 pub use chains::*;
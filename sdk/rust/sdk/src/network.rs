@@ -0,0 +1,24 @@
+//! Wormhole is deployed to several networks, each with its own contract addresses. The chain
+//! modules map a `Network` to the addresses of its deployment, so that a single binary can target
+//! any of them.
+
+/// A Wormhole deployment.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+
+    /// The local Tilt devnet.
+    Devnet,
+}
+
+/// The network targetted by feature gated helpers such as `id()`, chosen by enabling one of the
+/// `mainnet`, `testnet` or `devnet` features.
+#[cfg(feature = "mainnet")]
+pub const NETWORK: Network = Network::Mainnet;
+
+#[cfg(feature = "testnet")]
+pub const NETWORK: Network = Network::Testnet;
+
+#[cfg(feature = "devnet")]
+pub const NETWORK: Network = Network::Devnet;
@@ -0,0 +1,242 @@
+//! Structured events logged by the Wormhole bridge on Solana.
+//!
+//! Every state change an indexer may care about is logged with `sol_log_data` as a single data
+//! field, holding the event version followed by the borsh encoded `Event`. The runtime renders it
+//! as a `Program data: <base64>` log line, which `decode_logs` turns back into events.
+//!
+//! New events are appended to `Event` without changing the version, so decoders report events
+//! newer than themselves as `DecodeError::InvalidEvent`. Changing the layout of an existing event
+//! requires a new version.
+
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+use solana_program::pubkey::Pubkey;
+use std::fmt;
+
+/// Version of the event encoding, written before every event.
+pub const EVENT_VERSION: u8 = 1;
+
+/// Prefix of the log lines the runtime writes for `sol_log_data`.
+const PROGRAM_DATA: &str = "Program data: ";
+
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum Event {
+    /// A message was posted by an emitter, to be observed and signed by the guardians.
+    MessagePosted {
+        message: Pubkey,
+        emitter: Pubkey,
+        sequence: u64,
+        nonce: u32,
+        consistency_level: u8,
+        submission_time: u32,
+        payload: Vec<u8>,
+    },
+
+    /// A VAA was verified and posted.
+    VAAPosted {
+        posted_vaa: Pubkey,
+        hash: [u8; 32],
+        guardian_set_index: u32,
+        emitter_chain: u16,
+        emitter_address: [u8; 32],
+        sequence: u64,
+    },
+
+    /// Governance replaced the active guardian set.
+    GuardianSetUpgraded {
+        old_index: u32,
+        new_index: u32,
+        keys: Vec<[u8; 20]>,
+    },
+
+    /// Governance set the fee for posting messages.
+    FeesSet { fee: u64 },
+
+    /// Governance transferred collected fees.
+    FeesTransferred { recipient: Pubkey, amount: u64 },
+
+    /// Governance upgraded the bridge program.
+    ContractUpgraded { new_contract: Pubkey },
+
+    /// Governance set the fee charged for the messages of an emitter.
+    EmitterFeeSet {
+        emitter: Pubkey,
+        fee: u64,
+        fee_per_byte: u64,
+    },
+}
+
+impl Event {
+    /// Encode the event as logged by the bridge.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = vec![EVENT_VERSION];
+        self.serialize(&mut data).unwrap();
+        data
+    }
+
+    /// Decode an event from the data field of a `sol_log_data` log.
+    pub fn decode(data: &[u8]) -> Result<Event, DecodeError> {
+        match data.split_first() {
+            None => Err(DecodeError::Empty),
+            Some((&EVENT_VERSION, event)) => {
+                Event::try_from_slice(event).map_err(|_| DecodeError::InvalidEvent)
+            }
+            Some((&version, _)) => Err(DecodeError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Log the event from within the bridge program.
+    pub fn emit(&self) {
+        solana_program::log::sol_log_data(&[&self.encode()]);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The log carries no data.
+    Empty,
+
+    /// The log data is not valid base64.
+    InvalidBase64,
+
+    /// The event was encoded with a version this decoder does not know.
+    UnsupportedVersion(u8),
+
+    /// The event could not be decoded, it may have been added after this decoder was built.
+    InvalidEvent,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::Empty => write!(f, "empty event"),
+            DecodeError::InvalidBase64 => write!(f, "invalid base64 in event log"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported event version {}", version)
+            }
+            DecodeError::InvalidEvent => write!(f, "invalid event"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+}
+
+/// Decode the events logged by the bridge at `program_id` from the log messages of a transaction.
+///
+/// Invocations are tracked so that data logged by other programs, including programs invoked by
+/// the bridge or programs invoking it, is skipped. Events that fail to decode are returned as
+/// errors in their place, so that indexers can notice when they need to be upgraded.
+pub fn decode_logs<S: AsRef<str>>(
+    program_id: &Pubkey,
+    logs: &[S],
+) -> Vec<Result<Event, DecodeError>> {
+    let program_id = program_id.to_string();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        let log = log.as_ref();
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if invocations.last() == Some(&program_id.as_str()) {
+                events.push(decode_data(data));
+            }
+            continue;
+        }
+
+        // Invocations are logged as `Program <id> invoke [<depth>]` and end with either
+        // `Program <id> success` or `Program <id> failed: <error>`.
+        let mut words = log.split(' ');
+        match (words.next(), words.next(), words.next()) {
+            (Some("Program"), Some(id), Some("invoke")) => invocations.push(id),
+            (Some("Program"), Some(_), Some("success")) => {
+                invocations.pop();
+            }
+            (Some("Program"), Some(_), Some("failed:")) => {
+                invocations.pop();
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// Decode the base64 data fields of a `Program data:` log line, of which events use exactly one.
+fn decode_data(data: &str) -> Result<Event, DecodeError> {
+    let mut fields = data.split(' ');
+    match (fields.next(), fields.next()) {
+        (Some(field), None) => {
+            let data = base64::decode(field).map_err(|_| DecodeError::InvalidBase64)?;
+            Event::decode(&data)
+        }
+        _ => Err(DecodeError::InvalidEvent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_log(event: &Event) -> String {
+        format!("{}{}", PROGRAM_DATA, base64::encode(event.encode()))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let event = Event::MessagePosted {
+            message: Pubkey::new_unique(),
+            emitter: Pubkey::new_unique(),
+            sequence: 7,
+            nonce: 3,
+            consistency_level: 32,
+            submission_time: 1_000,
+            payload: vec![1, 2, 3],
+        };
+        assert_eq!(Event::decode(&event.encode()), Ok(event.clone()));
+
+        let mut encoded = event.encode();
+        encoded[0] = EVENT_VERSION + 1;
+        assert_eq!(
+            Event::decode(&encoded),
+            Err(DecodeError::UnsupportedVersion(EVENT_VERSION + 1))
+        );
+        assert_eq!(Event::decode(&[]), Err(DecodeError::Empty));
+        assert_eq!(Event::decode(&[EVENT_VERSION, 200]), Err(DecodeError::InvalidEvent));
+    }
+
+    #[test]
+    fn test_decode_logs() {
+        let bridge = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let fees = Event::FeesSet { fee: 100 };
+        let upgrade = Event::ContractUpgraded {
+            new_contract: Pubkey::new_unique(),
+        };
+
+        let logs = vec![
+            format!("Program {} invoke [1]", other),
+            data_log(&fees),
+            format!("Program {} invoke [2]", bridge),
+            "Program log: Sequence: 0".to_string(),
+            data_log(&upgrade),
+            format!("Program {} invoke [3]", other),
+            data_log(&fees),
+            format!("Program {} success", other),
+            format!("{}!!!", PROGRAM_DATA),
+            format!("Program {} consumed 100 of 200000 compute units", bridge),
+            format!("Program {} success", bridge),
+            data_log(&fees),
+            format!("Program {} failed: custom program error: 0x1", other),
+            format!("Program {} invoke [1]", bridge),
+            data_log(&fees),
+        ];
+
+        assert_eq!(
+            decode_logs(&bridge, &logs),
+            vec![Ok(upgrade), Err(DecodeError::InvalidBase64), Ok(fees)]
+        );
+    }
+}
@@ -1,17 +1,27 @@
 pub mod bridge;
 pub mod claim;
+pub mod emitter_fee;
 pub mod fee_collector;
 pub mod guardian_set;
 pub mod posted_message;
+pub mod posted_message_unreliable;
 pub mod posted_vaa;
+pub mod posted_vaa_expiry;
+pub mod quorum;
+pub mod rent_payer;
 pub mod sequence;
 pub mod signature_set;
 
 pub use bridge::*;
 pub use claim::*;
+pub use emitter_fee::*;
 pub use fee_collector::*;
 pub use guardian_set::*;
 pub use posted_message::*;
+pub use posted_message_unreliable::*;
 pub use posted_vaa::*;
+pub use posted_vaa_expiry::*;
+pub use quorum::*;
+pub use rent_payer::*;
 pub use sequence::*;
 pub use signature_set::*;
@@ -0,0 +1,56 @@
+//! The EmitterFee account holds a governance controlled message fee for a single emitter, which
+//! replaces the flat fee in the bridge config for the messages it posts.
+
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+use solana_program::pubkey::Pubkey;
+use solitaire::{
+    processors::seeded::Seeded,
+    AccountOwner,
+    AccountState,
+    Data,
+    Owned,
+};
+
+pub type EmitterFee<'b, const State: AccountState> = Data<'b, EmitterFeeData, { State }>;
+
+#[derive(Default, Clone, Copy, BorshDeserialize, BorshSerialize)]
+pub struct EmitterFeeData {
+    /// Fee in lamports charged for every message.
+    pub fee: u64,
+
+    /// Fee in lamports charged for every byte of the message payload.
+    pub fee_per_byte: u64,
+}
+
+impl EmitterFeeData {
+    /// Fee for a message with a payload of `len` bytes, or None if it overflows.
+    pub fn message_fee(&self, len: usize) -> Option<u64> {
+        self.fee_per_byte
+            .checked_mul(len as u64)?
+            .checked_add(self.fee)
+    }
+}
+
+pub struct EmitterFeeDerivationData<'a> {
+    pub emitter_key: &'a Pubkey,
+}
+
+impl<'a, 'b, const State: AccountState> Seeded<&EmitterFeeDerivationData<'b>>
+    for EmitterFee<'a, { State }>
+{
+    fn seeds(data: &EmitterFeeDerivationData) -> Vec<Vec<u8>> {
+        vec![
+            "EmitterFee".as_bytes().to_vec(),
+            data.emitter_key.to_bytes().to_vec(),
+        ]
+    }
+}
+
+impl Owned for EmitterFeeData {
+    fn owner(&self) -> AccountOwner {
+        AccountOwner::This
+    }
+}
@@ -0,0 +1,104 @@
+//! Unreliable messages are written to an emitter owned PDA that is overwritten by every message
+//! the emitter posts, so guardians observe them from the transaction log instead of the account.
+
+use crate::MessageData;
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+use solana_program::pubkey::Pubkey;
+use solitaire::{
+    processors::seeded::Seeded,
+    AccountOwner,
+    AccountState,
+    Data,
+    Owned,
+};
+use std::{
+    io::Write,
+    ops::{
+        Deref,
+        DerefMut,
+    },
+};
+
+pub type PostedMessageUnreliable<'a, const State: AccountState> =
+    Data<'a, PostedMessageUnreliableData, { State }>;
+
+pub struct PostedMessageUnreliableDerivationData<'a> {
+    pub emitter_key: &'a Pubkey,
+}
+
+impl<'a, 'b, const State: AccountState> Seeded<&PostedMessageUnreliableDerivationData<'b>>
+    for PostedMessageUnreliable<'a, { State }>
+{
+    fn seeds(data: &PostedMessageUnreliableDerivationData) -> Vec<Vec<u8>> {
+        vec![
+            "PostedMessageUnreliable".as_bytes().to_vec(),
+            data.emitter_key.to_bytes().to_vec(),
+        ]
+    }
+}
+
+/// Posted message with the same layout as a reliable one, but a different magic so that it is
+/// never mistaken for one.
+pub struct PostedMessageUnreliableData(pub MessageData);
+
+impl BorshSerialize for PostedMessageUnreliableData {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write(b"msu")?;
+        BorshSerialize::serialize(&self.0, writer)
+    }
+}
+
+impl BorshDeserialize for PostedMessageUnreliableData {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        *buf = &buf[3..];
+        let message = <MessageData as BorshDeserialize>::deserialize(buf)?;
+
+        // The account keeps the size of the largest message, smaller ones are padded with zeroes.
+        *buf = &[];
+        Ok(PostedMessageUnreliableData(message))
+    }
+}
+
+impl Deref for PostedMessageUnreliableData {
+    type Target = MessageData;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for PostedMessageUnreliableData {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl Default for PostedMessageUnreliableData {
+    fn default() -> Self {
+        PostedMessageUnreliableData(MessageData::default())
+    }
+}
+
+impl Clone for PostedMessageUnreliableData {
+    fn clone(&self) -> Self {
+        PostedMessageUnreliableData(self.0.clone())
+    }
+}
+
+#[cfg(not(feature = "cpi"))]
+impl Owned for PostedMessageUnreliableData {
+    fn owner(&self) -> AccountOwner {
+        AccountOwner::This
+    }
+}
+
+#[cfg(feature = "cpi")]
+impl Owned for PostedMessageUnreliableData {
+    fn owner(&self) -> AccountOwner {
+        use std::str::FromStr;
+        AccountOwner::Other(Pubkey::from_str(env!("BRIDGE_ADDRESS")).unwrap())
+    }
+}
@@ -0,0 +1,30 @@
+//! The PostedVAAExpiry account holds the governance controlled period after which posted VAAs can
+//! be closed to refund their rent. Until governance creates it, posted VAAs never expire.
+
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+use solitaire::{
+    AccountOwner,
+    AccountState,
+    Data,
+    Derive,
+    Owned,
+};
+
+pub type PostedVAAExpiry<'a, const State: AccountState> =
+    Derive<Data<'a, PostedVAAExpiryData, { State }>, "PostedVAAExpiry">;
+
+#[derive(Clone, Default, BorshSerialize, BorshDeserialize)]
+pub struct PostedVAAExpiryData {
+    /// Seconds after the VAA timestamp before a posted VAA can be closed, or 0 if posted VAAs
+    /// never expire.
+    pub expiration_time: u32,
+}
+
+impl Owned for PostedVAAExpiryData {
+    fn owner(&self) -> AccountOwner {
+        AccountOwner::This
+    }
+}
@@ -0,0 +1,42 @@
+//! The Quorum account holds a governance controlled signature threshold for a single guardian set.
+//! Guardian sets without one require the default 2/3+1 of their guardians.
+
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+use solitaire::{
+    processors::seeded::Seeded,
+    AccountOwner,
+    AccountState,
+    Data,
+    Owned,
+};
+
+pub type Quorum<'b, const State: AccountState> = Data<'b, QuorumData, { State }>;
+
+#[derive(Default, Clone, Copy, BorshDeserialize, BorshSerialize)]
+pub struct QuorumData {
+    /// Number of signatures required to reach consensus. 0 means the default is required.
+    pub quorum: u8,
+}
+
+/// Quorum account PDAs are indexed by the version number of their guardian set.
+pub struct QuorumDerivationData {
+    pub guardian_set_index: u32,
+}
+
+impl<'a, const State: AccountState> Seeded<&QuorumDerivationData> for Quorum<'a, { State }> {
+    fn seeds(data: &QuorumDerivationData) -> Vec<Vec<u8>> {
+        vec![
+            "Quorum".as_bytes().to_vec(),
+            data.guardian_set_index.to_be_bytes().to_vec(),
+        ]
+    }
+}
+
+impl Owned for QuorumData {
+    fn owner(&self) -> AccountOwner {
+        AccountOwner::This
+    }
+}
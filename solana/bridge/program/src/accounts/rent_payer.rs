@@ -0,0 +1,46 @@
+//! The RentPayer account records who paid for a posted VAA or signature set, so that its rent can
+//! be refunded to them when it is closed. It lives beside the account it describes rather than
+//! inside it, leaving the layout existing readers of those accounts parse untouched.
+
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+use solana_program::pubkey::Pubkey;
+use solitaire::{
+    processors::seeded::Seeded,
+    AccountOwner,
+    AccountState,
+    Data,
+    Owned,
+};
+
+pub type RentPayer<'b, const State: AccountState> = Data<'b, RentPayerData, { State }>;
+
+#[derive(Default, Clone, Copy, BorshDeserialize, BorshSerialize)]
+pub struct RentPayerData {
+    /// Account refunded when the described account is closed.
+    pub payer: Pubkey,
+}
+
+pub struct RentPayerDerivationData<'a> {
+    /// The posted VAA or signature set the payer paid for.
+    pub account_key: &'a Pubkey,
+}
+
+impl<'a, 'b, const State: AccountState> Seeded<&RentPayerDerivationData<'b>>
+    for RentPayer<'a, { State }>
+{
+    fn seeds(data: &RentPayerDerivationData) -> Vec<Vec<u8>> {
+        vec![
+            "RentPayer".as_bytes().to_vec(),
+            data.account_key.to_bytes().to_vec(),
+        ]
+    }
+}
+
+impl Owned for RentPayerData {
+    fn owner(&self) -> AccountOwner {
+        AccountOwner::This
+    }
+}
@@ -10,19 +10,51 @@ use solitaire::{
     Data,
     Owned,
 };
+use std::io::Write;
 
 pub type Sequence<'b> = Data<'b, SequenceTracker, { AccountState::MaybeInitialized }>;
 
-#[derive(Default, Clone, Copy, BorshDeserialize, BorshSerialize)]
+#[derive(Default, Clone, Copy)]
 pub struct SequenceTracker {
     pub sequence: u64,
+
+    /// Set by governance once the emitter has an EmitterFee account, which must then be passed
+    /// when posting messages so the override cannot be skipped.
+    pub emitter_fee: bool,
+}
+
+impl BorshSerialize for SequenceTracker {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&self.sequence, writer)?;
+
+        // The flag only fits once SetEmitterFee has grown the tracker, so the trackers of every
+        // other emitter remain a bare sequence number.
+        if self.emitter_fee {
+            BorshSerialize::serialize(&self.emitter_fee, writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for SequenceTracker {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let sequence = BorshDeserialize::deserialize(buf)?;
+        let emitter_fee = match buf.len() {
+            0 => false,
+            _ => BorshDeserialize::deserialize(buf)?,
+        };
+        Ok(SequenceTracker {
+            sequence,
+            emitter_fee,
+        })
+    }
 }
 
 pub struct SequenceDerivationData<'a> {
     pub emitter_key: &'a Pubkey,
 }
 
-impl<'b> Seeded<&SequenceDerivationData<'b>> for Sequence<'b> {
+impl<'a, 'b> Seeded<&SequenceDerivationData<'b>> for Sequence<'a> {
     fn seeds(data: &SequenceDerivationData) -> Vec<Vec<u8>> {
         vec![
             "Sequence".as_bytes().to_vec(),
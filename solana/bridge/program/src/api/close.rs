@@ -0,0 +1,257 @@
+use solitaire::*;
+
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+use solana_program::{
+    self,
+    account_info::AccountInfo,
+    program::{
+        invoke,
+        invoke_signed,
+    },
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::clock::Clock,
+};
+
+use crate::{
+    accounts::{
+        PostedVAA,
+        PostedVAAData,
+        PostedVAADerivationData,
+        PostedVAAExpiry,
+        RentPayer,
+        RentPayerData,
+        RentPayerDerivationData,
+        SignatureSetData,
+    },
+    api::post_vaa::body_hash,
+    error::Error::{
+        InvalidHash,
+        InvalidPayer,
+        PostedVAANotExpired,
+    },
+    PostVAAData,
+};
+use solitaire::{
+    processors::seeded::Seeded,
+    CreationLamports::Exempt,
+};
+
+#[derive(FromAccounts)]
+pub struct CloseSignatureSet<'b> {
+    /// Account that paid for the signature set, receiving its rent.
+    pub payer: Mut<Info<'b>>,
+
+    /// Signature set to close.
+    pub signature_set: Mut<Info<'b>>,
+
+    /// VAA posted with the signature set.
+    pub posted_vaa: PostedVAA<'b, { AccountState::Initialized }>,
+
+    /// Record of the account that paid for the signature set, closed along with it.
+    pub rent_payer: Mut<Info<'b>>,
+}
+
+impl<'b> InstructionContext<'b> for CloseSignatureSet<'b> {
+}
+
+#[derive(Default, BorshSerialize, BorshDeserialize)]
+pub struct CloseSignatureSetData {}
+
+/// Close a signature set once the VAA it verified has been posted, refunding its rent to the
+/// payer. The signatures are no longer needed as the posted VAA is proof of them.
+pub fn close_signature_set(
+    ctx: &ExecutionContext,
+    accs: &mut CloseSignatureSet,
+    _data: CloseSignatureSetData,
+) -> Result<()> {
+    if accs.signature_set.owner != ctx.program_id {
+        return Err(ProgramError::IllegalOwner.into());
+    }
+
+    let signature_set = SignatureSetData::try_from_slice(&accs.signature_set.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    accs.posted_vaa.verify_derivation(
+        ctx.program_id,
+        &PostedVAADerivationData {
+            payload_hash: signature_set.hash.to_vec(),
+        },
+    )?;
+
+    check_rent_payer(ctx, &accs.rent_payer, accs.signature_set.key, accs.payer.key)?;
+
+    close_account(&accs.signature_set, &accs.payer);
+    close_account(&accs.rent_payer, &accs.payer);
+    Ok(())
+}
+
+#[derive(FromAccounts)]
+pub struct ClosePostedVAA<'b> {
+    /// Account that paid for the posted VAA, receiving its rent.
+    pub payer: Mut<Info<'b>>,
+
+    /// Expiry set by governance, after which posted VAAs can be closed.
+    pub expiry: PostedVAAExpiry<'b, { AccountState::Initialized }>,
+
+    /// Posted VAA to close.
+    pub posted_vaa: Mut<Info<'b>>,
+
+    /// Clock used to check the expiry.
+    pub clock: Sysvar<'b, Clock>,
+
+    /// Record of the account that paid for the posted VAA, closed along with it.
+    pub rent_payer: Mut<Info<'b>>,
+}
+
+impl<'b> InstructionContext<'b> for ClosePostedVAA<'b> {
+}
+
+#[derive(Default, BorshSerialize, BorshDeserialize)]
+pub struct ClosePostedVAAData {}
+
+/// Close a posted VAA once it has expired, refunding its rent to the payer. Replay protection of
+/// VAAs is the job of the claim accounts, so consumers must not rely on posted VAAs existing.
+pub fn close_posted_vaa(
+    ctx: &ExecutionContext,
+    accs: &mut ClosePostedVAA,
+    _data: ClosePostedVAAData,
+) -> Result<()> {
+    // Posted messages share the layout of posted VAAs, so check the magic before reading.
+    if accs.posted_vaa.owner != ctx.program_id {
+        return Err(ProgramError::IllegalOwner.into());
+    }
+    if !accs.posted_vaa.data.borrow().starts_with(b"vaa") {
+        return Err(ProgramError::InvalidAccountData.into());
+    }
+
+    let posted_vaa = PostedVAAData::try_from_slice(&accs.posted_vaa.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    // The guardian set index is not part of the signed body, so it does not affect the hash.
+    let body = PostVAAData {
+        version: posted_vaa.vaa_version,
+        guardian_set_index: 0,
+        timestamp: posted_vaa.vaa_time,
+        nonce: posted_vaa.nonce,
+        emitter_chain: posted_vaa.emitter_chain,
+        emitter_address: posted_vaa.emitter_address,
+        sequence: posted_vaa.sequence,
+        consistency_level: posted_vaa.consistency_level,
+        payload: posted_vaa.payload.clone(),
+    };
+    let derivation = PostedVAADerivationData {
+        payload_hash: body_hash(&body)?.to_vec(),
+    };
+    if PostedVAA::<'_, { AccountState::Initialized }>::key(&derivation, ctx.program_id)
+        != *accs.posted_vaa.key
+    {
+        return Err(InvalidHash.into());
+    }
+
+    let expiration_time = accs.expiry.expiration_time;
+    if expiration_time == 0
+        || (posted_vaa.vaa_time as i64 + expiration_time as i64) >= accs.clock.unix_timestamp
+    {
+        return Err(PostedVAANotExpired.into());
+    }
+
+    check_rent_payer(ctx, &accs.rent_payer, accs.posted_vaa.key, accs.payer.key)?;
+
+    close_account(&accs.posted_vaa, &accs.payer);
+    close_account(&accs.rent_payer, &accs.payer);
+    Ok(())
+}
+
+/// Record the payer of a newly created posted VAA or signature set in its RentPayer account, so
+/// the rent can be refunded once it is closed. The RentPayer account is read from the trailing
+/// accounts of the instruction, accounts created by clients that do not pass it can never be
+/// closed.
+pub(crate) fn record_rent_payer(
+    ctx: &ExecutionContext,
+    payer: &Pubkey,
+    account: &Pubkey,
+) -> Result<()> {
+    let derivation = RentPayerDerivationData {
+        account_key: account,
+    };
+    let seeds = RentPayer::<'_, { AccountState::Uninitialized }>::seeds(&derivation);
+    let mut seeds: Vec<&[u8]> = seeds.iter().map(|seed| seed.as_slice()).collect();
+    let (key, bump) = Pubkey::find_program_address(&seeds, ctx.program_id);
+    let info = match ctx.accounts.iter().find(|info| *info.key == key) {
+        Some(info) => info,
+        None => return Ok(()),
+    };
+
+    let bump = [bump];
+    seeds.push(&bump);
+    let data = RentPayerData { payer: *payer }.try_to_vec()?;
+    let size = data.len() as u64;
+    let lamports = Exempt.amount(data.len());
+
+    if info.lamports() == 0 {
+        let ix = system_instruction::create_account(payer, &key, lamports, size, ctx.program_id);
+        invoke_signed(&ix, ctx.accounts, &[&seeds])?;
+    } else {
+        // Anyone can send lamports to the address beforehand, which makes create_account fail,
+        // so a funded account is topped up to rent exemption and allocated in place instead.
+        let shortfall = lamports.saturating_sub(info.lamports());
+        if shortfall > 0 {
+            invoke(
+                &system_instruction::transfer(payer, &key, shortfall),
+                ctx.accounts,
+            )?;
+        }
+        invoke_signed(
+            &system_instruction::allocate(&key, size),
+            ctx.accounts,
+            &[&seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(&key, ctx.program_id),
+            ctx.accounts,
+            &[&seeds],
+        )?;
+    }
+
+    info.data.borrow_mut().copy_from_slice(&data);
+    Ok(())
+}
+
+/// Check that `rent_payer` is the RentPayer account of `account` and records `payer`.
+fn check_rent_payer(
+    ctx: &ExecutionContext,
+    rent_payer: &AccountInfo,
+    account: &Pubkey,
+    payer: &Pubkey,
+) -> Result<()> {
+    let key = RentPayer::<'_, { AccountState::Initialized }>::key(
+        &RentPayerDerivationData {
+            account_key: account,
+        },
+        ctx.program_id,
+    );
+    if *rent_payer.key != key || rent_payer.owner != ctx.program_id {
+        return Err(InvalidPayer.into());
+    }
+
+    let record = RentPayerData::try_from_slice(&rent_payer.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    if record.payer != *payer {
+        return Err(InvalidPayer.into());
+    }
+    Ok(())
+}
+
+/// Move all lamports to the recipient and wipe the account, which the runtime then garbage
+/// collects. The data is zeroed so the account cannot be revived within the same transaction.
+fn close_account(account: &AccountInfo, recipient: &AccountInfo) {
+    let lamports = account.lamports();
+    **recipient.lamports.borrow_mut() += lamports;
+    **account.lamports.borrow_mut() = 0;
+    account.data.borrow_mut().fill(0);
+}
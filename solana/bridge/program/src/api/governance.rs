@@ -1,7 +1,12 @@
 use solitaire::*;
 
+use primitive_types::U256;
 use solana_program::{
-    program::invoke_signed,
+    account_info::AccountInfo,
+    program::{
+        invoke,
+        invoke_signed,
+    },
     pubkey::Pubkey,
     sysvar::{
         clock::Clock,
@@ -16,23 +21,38 @@ use solitaire::{
 use crate::{
     accounts::{
         Bridge,
+        EmitterFee,
+        EmitterFeeDerivationData,
         GuardianSet,
         GuardianSetDerivationData,
+        PostedVAAExpiry,
+        Quorum,
+        QuorumDerivationData,
+        Sequence,
+        SequenceDerivationData,
     },
+    api::post_vaa::default_consensus_count,
     error::Error::{
+        GuardianSetMismatch,
+        InvalidEmitterFee,
         InvalidFeeRecipient,
         InvalidGovernanceKey,
         InvalidGovernanceWithdrawal,
         InvalidGuardianSetUpgrade,
+        InvalidQuorum,
     },
     types::{
         GovernancePayloadGuardianSetChange,
+        GovernancePayloadSetEmitterFee,
         GovernancePayloadSetMessageFee,
+        GovernancePayloadSetPostedVAAExpiry,
+        GovernancePayloadSetQuorum,
         GovernancePayloadTransferFees,
         GovernancePayloadUpgrade,
     },
     vaa::ClaimableVAA,
     DeserializePayload,
+    Event,
     CHAIN_ID_SOLANA,
 };
 
@@ -115,6 +135,11 @@ pub fn upgrade_contract(
     let seeds = seeds.as_slice();
     invoke_signed(&upgrade_ix, ctx.accounts, &[seeds])?;
 
+    Event::ContractUpgraded {
+        new_contract: accs.vaa.message.new_contract,
+    }
+    .emit();
+
     Ok(())
 }
 
@@ -197,6 +222,13 @@ pub fn upgrade_guardian_set(
     // Set guardian set index
     accs.bridge.guardian_set_index = accs.vaa.new_guardian_set_index;
 
+    Event::GuardianSetUpgraded {
+        old_index: accs.guardian_set_old.index,
+        new_index: accs.guardian_set_new.index,
+        keys: accs.guardian_set_new.keys.clone(),
+    }
+    .emit();
+
     Ok(())
 }
 
@@ -224,6 +256,11 @@ pub fn set_fees(ctx: &ExecutionContext, accs: &mut SetFees, _data: SetFeesData)
     accs.vaa.claim(ctx, accs.payer.key)?;
     accs.bridge.config.fee = accs.vaa.fee.as_u64();
 
+    Event::FeesSet {
+        fee: accs.bridge.config.fee,
+    }
+    .emit();
+
     Ok(())
 }
 
@@ -290,5 +327,238 @@ pub fn transfer_fees(
     let seeds = seeds.as_slice();
     invoke_signed(&transfer_ix, ctx.accounts, &[seeds])?;
 
+    Event::FeesTransferred {
+        recipient: *accs.recipient.key,
+        amount: accs.vaa.amount.as_u64(),
+    }
+    .emit();
+
+    Ok(())
+}
+
+#[derive(FromAccounts)]
+pub struct SetPostedVAAExpiry<'b> {
+    /// Payer for account creation (vaa-claim)
+    pub payer: Mut<Signer<Info<'b>>>,
+
+    /// Bridge config
+    pub bridge: Bridge<'b, { AccountState::Initialized }>,
+
+    /// Governance VAA
+    pub vaa: ClaimableVAA<'b, GovernancePayloadSetPostedVAAExpiry>,
+
+    /// Posted VAA expiry, created the first time it is set
+    pub expiry: Mut<PostedVAAExpiry<'b, { AccountState::MaybeInitialized }>>,
+}
+
+impl<'b> InstructionContext<'b> for SetPostedVAAExpiry<'b> {
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct SetPostedVAAExpiryData {}
+
+pub fn set_posted_vaa_expiry(
+    ctx: &ExecutionContext,
+    accs: &mut SetPostedVAAExpiry,
+    _data: SetPostedVAAExpiryData,
+) -> Result<()> {
+    verify_governance(&accs.vaa)?;
+    accs.vaa.verify(ctx.program_id)?;
+    accs.vaa.claim(ctx, accs.payer.key)?;
+    accs.expiry.expiration_time = accs.vaa.expiration_time;
+
+    if !accs.expiry.is_initialized() {
+        let size = accs.expiry.size();
+        let create_ix = solana_program::system_instruction::create_account(
+            accs.payer.key,
+            accs.expiry.info().key,
+            Exempt.amount(size),
+            size as u64,
+            ctx.program_id,
+        );
+
+        let seeds = accs.expiry.self_bumped_seeds(None, ctx.program_id);
+        let seeds: Vec<&[u8]> = seeds.iter().map(|item| item.as_slice()).collect();
+        let seeds = seeds.as_slice();
+        invoke_signed(&create_ix, ctx.accounts, &[seeds])?;
+    }
+
+    Ok(())
+}
+
+#[derive(FromAccounts)]
+pub struct SetQuorum<'b> {
+    /// Payer for account creation (vaa-claim) and for the quorum account
+    pub payer: Mut<Signer<Info<'b>>>,
+
+    /// Bridge config
+    pub bridge: Bridge<'b, { AccountState::Initialized }>,
+
+    /// Governance VAA
+    pub vaa: ClaimableVAA<'b, GovernancePayloadSetQuorum>,
+
+    /// Active guardian set
+    pub guardian_set: GuardianSet<'b, { AccountState::Initialized }>,
+
+    /// Quorum of the active guardian set, created the first time it is set
+    pub quorum: Mut<Quorum<'b, { AccountState::MaybeInitialized }>>,
+}
+
+impl<'b> InstructionContext<'b> for SetQuorum<'b> {
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct SetQuorumData {}
+
+pub fn set_quorum(
+    ctx: &ExecutionContext,
+    accs: &mut SetQuorum,
+    _data: SetQuorumData,
+) -> Result<()> {
+    // Only the active guardian set can be changed.
+    if accs.vaa.guardian_set_index != accs.bridge.guardian_set_index {
+        return Err(GuardianSetMismatch.into());
+    }
+
+    // Clients that do not pass the quorum account are held to the default, so a quorum above it
+    // could be bypassed and is rejected.
+    if accs.vaa.quorum as usize > default_consensus_count(&accs.guardian_set) {
+        return Err(InvalidQuorum.into());
+    }
+
+    let derivation_data = QuorumDerivationData {
+        guardian_set_index: accs.vaa.guardian_set_index,
+    };
+
+    verify_governance(&accs.vaa)?;
+    accs.vaa.verify(ctx.program_id)?;
+    accs.guardian_set.verify_derivation(
+        ctx.program_id,
+        &GuardianSetDerivationData {
+            index: accs.vaa.guardian_set_index,
+        },
+    )?;
+    accs.quorum
+        .verify_derivation(ctx.program_id, &derivation_data)?;
+    accs.vaa.claim(ctx, accs.payer.key)?;
+    accs.quorum.quorum = accs.vaa.quorum;
+
+    if !accs.quorum.is_initialized() {
+        accs.quorum
+            .create(&derivation_data, ctx, accs.payer.key, Exempt)?;
+    }
+
+    Ok(())
+}
+
+#[derive(FromAccounts)]
+pub struct SetEmitterFee<'b> {
+    /// Payer for account creation (vaa-claim) and for the fee accounts
+    pub payer: Mut<Signer<Info<'b>>>,
+
+    /// Bridge config
+    pub bridge: Bridge<'b, { AccountState::Initialized }>,
+
+    /// Governance VAA
+    pub vaa: ClaimableVAA<'b, GovernancePayloadSetEmitterFee>,
+
+    /// Fee of the emitter, created the first time it is set
+    pub emitter_fee: Mut<EmitterFee<'b, { AccountState::MaybeInitialized }>>,
+
+    /// Sequence tracker of the emitter, flagged so that its messages must pay the emitter fee
+    pub sequence: Mut<Sequence<'b>>,
+}
+
+impl<'b> InstructionContext<'b> for SetEmitterFee<'b> {
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct SetEmitterFeeData {}
+
+pub fn set_emitter_fee(
+    ctx: &ExecutionContext,
+    accs: &mut SetEmitterFee,
+    _data: SetEmitterFeeData,
+) -> Result<()> {
+    let emitter = accs.vaa.emitter;
+
+    verify_governance(&accs.vaa)?;
+    accs.vaa.verify(ctx.program_id)?;
+    accs.emitter_fee.verify_derivation(
+        ctx.program_id,
+        &EmitterFeeDerivationData {
+            emitter_key: &emitter,
+        },
+    )?;
+    accs.sequence.verify_derivation(
+        ctx.program_id,
+        &SequenceDerivationData {
+            emitter_key: &emitter,
+        },
+    )?;
+    // Fees are stored as u64 lamports, larger values cannot be charged.
+    if accs.vaa.fee > U256::from(u64::MAX) || accs.vaa.fee_per_byte > U256::from(u64::MAX) {
+        return Err(InvalidEmitterFee.into());
+    }
+
+    accs.vaa.claim(ctx, accs.payer.key)?;
+    accs.emitter_fee.fee = accs.vaa.fee.as_u64();
+    accs.emitter_fee.fee_per_byte = accs.vaa.fee_per_byte.as_u64();
+    accs.sequence.emitter_fee = true;
+
+    if !accs.emitter_fee.is_initialized() {
+        accs.emitter_fee.create(
+            &EmitterFeeDerivationData {
+                emitter_key: &emitter,
+            },
+            ctx,
+            accs.payer.key,
+            Exempt,
+        )?;
+    }
+
+    // Emitters that have not posted yet get their sequence tracker here, while existing trackers
+    // have no room for the flag.
+    if !accs.sequence.is_initialized() {
+        accs.sequence.create(
+            &SequenceDerivationData {
+                emitter_key: &emitter,
+            },
+            ctx,
+            accs.payer.key,
+            Exempt,
+        )?;
+    } else {
+        let size = accs.sequence.size();
+        grow_account(ctx, accs.payer.key, accs.sequence.info(), size)?;
+    }
+
+    Event::EmitterFeeSet {
+        emitter,
+        fee: accs.emitter_fee.fee,
+        fee_per_byte: accs.emitter_fee.fee_per_byte,
+    }
+    .emit();
+
+    Ok(())
+}
+
+/// Grow an account created before new fields were added to it, topping up its rent first.
+fn grow_account(
+    ctx: &ExecutionContext,
+    payer: &Pubkey,
+    info: &AccountInfo,
+    size: usize,
+) -> Result<()> {
+    if info.data_len() >= size {
+        return Ok(());
+    }
+
+    let lamports = Exempt.amount(size).saturating_sub(info.lamports());
+    if lamports > 0 {
+        let transfer_ix = solana_program::system_instruction::transfer(payer, info.key, lamports);
+        invoke(&transfer_ix, ctx.accounts)?;
+    }
+    info.realloc(size, false)?;
     Ok(())
 }
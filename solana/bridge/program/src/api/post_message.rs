@@ -1,20 +1,33 @@
 use crate::{
     accounts::{
         Bridge,
+        EmitterFee,
+        EmitterFeeData,
+        EmitterFeeDerivationData,
         FeeCollector,
+        MessageData,
         PostedMessage,
+        PostedMessageUnreliable,
+        PostedMessageUnreliableDerivationData,
         Sequence,
         SequenceDerivationData,
     },
     error::Error::{
         InsufficientFees,
         MathOverflow,
+        MessageTooLarge,
+        MissingEmitterFee,
     },
     types::ConsistencyLevel,
+    Event,
     CHAIN_ID_SOLANA,
 };
 use solana_program::{
+    account_info::AccountInfo,
+    instruction::AccountMeta,
     msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
     sysvar::clock::Clock,
 };
 use solitaire::{
@@ -84,25 +97,14 @@ pub fn post_message(
     accs.sequence
         .verify_derivation(ctx.program_id, &(&*accs).into())?;
 
-    let fee = accs.bridge.config.fee;
-    // Fee handling, checking previously known balance allows us to not care who is the payer of
-    // this submission.
-    if accs
-        .fee_collector
-        .lamports()
-        .checked_sub(accs.bridge.last_lamports)
-        .ok_or(MathOverflow)?
-        < fee
-    {
-        trace!(
-            "Expected fee not found: fee, last_lamports, collector: {} {} {}",
-            fee,
-            accs.bridge.last_lamports,
-            accs.fee_collector.lamports(),
-        );
-        return Err(InsufficientFees.into());
-    }
-    accs.bridge.last_lamports = accs.fee_collector.lamports();
+    let fee = message_fee(
+        ctx,
+        &accs.bridge,
+        accs.sequence.emitter_fee,
+        accs.emitter.key,
+        data.payload.len(),
+    )?;
+    collect_fee(&mut accs.bridge, &accs.fee_collector, fee)?;
 
     // Init sequence tracker if it does not exist yet.
     if !accs.sequence.is_initialized() {
@@ -116,16 +118,13 @@ pub fn post_message(
 
     // Initialize transfer
     trace!("Setting Message Details");
-    accs.message.submission_time = accs.clock.unix_timestamp as u32;
-    accs.message.emitter_chain = CHAIN_ID_SOLANA;
-    accs.message.emitter_address = accs.emitter.key.to_bytes();
-    accs.message.nonce = data.nonce;
-    accs.message.payload = data.payload;
-    accs.message.sequence = accs.sequence.sequence;
-    accs.message.consistency_level = match data.consistency_level {
-        ConsistencyLevel::Confirmed => 1,
-        ConsistencyLevel::Finalized => 32,
-    };
+    write_message(
+        &mut accs.message,
+        accs.emitter.key,
+        accs.sequence.sequence,
+        &accs.clock,
+        data,
+    );
 
     // Create message account
     let size = accs.message.size();
@@ -138,9 +137,256 @@ pub fn post_message(
     );
     solana_program::program::invoke(&ix, ctx.accounts)?;
 
+    message_posted(accs.message.info().key, &accs.message).emit();
+
     // Bump sequence number
     trace!("New Sequence: {}", accs.sequence.sequence + 1);
     accs.sequence.sequence += 1;
 
     Ok(())
 }
+
+impl<'a> From<&PostMessageUnreliable<'a>> for SequenceDerivationData<'a> {
+    fn from(accs: &PostMessageUnreliable<'a>) -> Self {
+        SequenceDerivationData {
+            emitter_key: accs.emitter.key,
+        }
+    }
+}
+
+impl<'a> From<&PostMessageUnreliable<'a>> for PostedMessageUnreliableDerivationData<'a> {
+    fn from(accs: &PostMessageUnreliable<'a>) -> Self {
+        PostedMessageUnreliableDerivationData {
+            emitter_key: accs.emitter.key,
+        }
+    }
+}
+
+#[derive(FromAccounts)]
+pub struct PostMessageUnreliable<'b> {
+    /// Bridge config needed for fee calculation.
+    pub bridge: Mut<Bridge<'b, { AccountState::Initialized }>>,
+
+    /// Account of the emitter that is overwritten by every message it posts
+    pub message: Mut<PostedMessageUnreliable<'b, { AccountState::MaybeInitialized }>>,
+
+    /// Emitter of the VAA
+    pub emitter: Signer<MaybeMut<Info<'b>>>,
+
+    /// Tracker for the emitter sequence
+    pub sequence: Mut<Sequence<'b>>,
+
+    /// Payer for account creation
+    pub payer: Mut<Signer<Info<'b>>>,
+
+    /// Account to collect tx fee
+    pub fee_collector: Mut<FeeCollector<'b>>,
+
+    pub clock: Sysvar<'b, Clock>,
+}
+
+impl<'b> InstructionContext<'b> for PostMessageUnreliable<'b> {
+}
+
+/// Post a message without keeping it on chain. The message account of the emitter is created by
+/// its first message and overwritten by every later one, which must not be larger. Guardians
+/// observe the message from the transaction log, as the account may be overwritten before they
+/// read it.
+pub fn post_message_unreliable(
+    ctx: &ExecutionContext,
+    accs: &mut PostMessageUnreliable,
+    data: PostMessageData,
+) -> Result<()> {
+    trace!("Message Address: {}", accs.message.info().key);
+    trace!("Emitter Address: {}", accs.emitter.info().key);
+    trace!("Nonce: {}", data.nonce);
+
+    accs.message
+        .verify_derivation(ctx.program_id, &(&*accs).into())?;
+    accs.sequence
+        .verify_derivation(ctx.program_id, &(&*accs).into())?;
+
+    let fee = message_fee(
+        ctx,
+        &accs.bridge,
+        accs.sequence.emitter_fee,
+        accs.emitter.key,
+        data.payload.len(),
+    )?;
+    collect_fee(&mut accs.bridge, &accs.fee_collector, fee)?;
+
+    // Init sequence tracker if it does not exist yet.
+    if !accs.sequence.is_initialized() {
+        trace!("Initializing Sequence account to 0.");
+        accs.sequence
+            .create(&(&*accs).into(), ctx, accs.payer.key, Exempt)?;
+    }
+
+    // DO NOT REMOVE - CRITICAL OUTPUT
+    msg!("Sequence: {}", accs.sequence.sequence);
+
+    trace!("Setting Message Details");
+    write_message(
+        &mut accs.message,
+        accs.emitter.key,
+        accs.sequence.sequence,
+        &accs.clock,
+        data,
+    );
+
+    // Reuse the message account if the message fits, clearing the previous message so that a
+    // smaller one is padded with zeroes.
+    let size = accs.message.size();
+    if accs.message.is_initialized() {
+        if size > accs.message.info().data_len() {
+            return Err(MessageTooLarge.into());
+        }
+        accs.message.info().data.borrow_mut().fill(0);
+    } else {
+        let ix = solana_program::system_instruction::create_account(
+            accs.payer.key,
+            accs.message.info().key,
+            Exempt.amount(size),
+            size as u64,
+            ctx.program_id,
+        );
+        let seeds = accs
+            .message
+            .self_bumped_seeds(&(&*accs).into(), ctx.program_id);
+        let seeds: Vec<&[u8]> = seeds.iter().map(|item| item.as_slice()).collect();
+        let seeds = seeds.as_slice();
+        invoke_signed(&ix, ctx.accounts, &[seeds])?;
+    }
+
+    message_posted(accs.message.info().key, &accs.message).emit();
+
+    // Bump sequence number
+    trace!("New Sequence: {}", accs.sequence.sequence + 1);
+    accs.sequence.sequence += 1;
+
+    Ok(())
+}
+
+/// Fee for a message of the emitter, which is the flat bridge fee unless governance has set a fee
+/// for the emitter. The EmitterFee account is then read from the trailing accounts of the
+/// instruction, so that clients of emitters without one can keep the legacy account list.
+fn message_fee(
+    ctx: &ExecutionContext,
+    bridge: &Bridge<'_, { AccountState::Initialized }>,
+    has_emitter_fee: bool,
+    emitter: &Pubkey,
+    len: usize,
+) -> Result<u64> {
+    if !has_emitter_fee {
+        return Ok(bridge.config.fee);
+    }
+
+    let key = EmitterFee::<'_, { AccountState::Initialized }>::key(
+        &EmitterFeeDerivationData {
+            emitter_key: emitter,
+        },
+        ctx.program_id,
+    );
+    let info = ctx
+        .accounts
+        .iter()
+        .find(|info| *info.key == key && info.owner == ctx.program_id)
+        .ok_or(MissingEmitterFee)?;
+    let emitter_fee = EmitterFeeData::try_from_slice(&info.data.borrow())?;
+    trace!(
+        "Emitter fee: {} + {} per byte",
+        emitter_fee.fee,
+        emitter_fee.fee_per_byte
+    );
+    emitter_fee
+        .message_fee(len)
+        .ok_or_else(|| MathOverflow.into())
+}
+
+/// Fee a program posting messages through CPI has to pay for a message of its emitter, along with
+/// the EmitterFee account to append to the post_message accounts. The account is only returned
+/// when the caller passed it, so that clients of emitters without a fee keep their account list.
+pub fn cpi_message_fee(
+    accounts: &[AccountInfo],
+    bridge_id: &Pubkey,
+    bridge_fee: u64,
+    emitter: &Pubkey,
+    len: usize,
+) -> Result<(u64, Option<AccountMeta>)> {
+    let key = EmitterFee::<'_, { AccountState::Initialized }>::key(
+        &EmitterFeeDerivationData {
+            emitter_key: emitter,
+        },
+        bridge_id,
+    );
+    let info = match accounts
+        .iter()
+        .find(|info| *info.key == key && info.owner == bridge_id)
+    {
+        Some(info) => info,
+        None => return Ok((bridge_fee, None)),
+    };
+
+    let fee = EmitterFeeData::try_from_slice(&info.data.borrow())?
+        .message_fee(len)
+        .ok_or(MathOverflow)?;
+    Ok((fee, Some(AccountMeta::new_readonly(key, false))))
+}
+
+/// Fee handling, checking previously known balance allows us to not care who is the payer of this
+/// submission.
+fn collect_fee(
+    bridge: &mut Bridge<'_, { AccountState::Initialized }>,
+    fee_collector: &FeeCollector<'_>,
+    fee: u64,
+) -> Result<()> {
+    if fee_collector
+        .lamports()
+        .checked_sub(bridge.last_lamports)
+        .ok_or(MathOverflow)?
+        < fee
+    {
+        trace!(
+            "Expected fee not found: fee, last_lamports, collector: {} {} {}",
+            fee,
+            bridge.last_lamports,
+            fee_collector.lamports(),
+        );
+        return Err(InsufficientFees.into());
+    }
+    bridge.last_lamports = fee_collector.lamports();
+    Ok(())
+}
+
+fn write_message(
+    message: &mut MessageData,
+    emitter: &Pubkey,
+    sequence: u64,
+    clock: &Clock,
+    data: PostMessageData,
+) {
+    message.submission_time = clock.unix_timestamp as u32;
+    message.emitter_chain = CHAIN_ID_SOLANA;
+    message.emitter_address = emitter.to_bytes();
+    message.nonce = data.nonce;
+    message.payload = data.payload;
+    message.sequence = sequence;
+    message.consistency_level = match data.consistency_level {
+        ConsistencyLevel::Confirmed => 1,
+        ConsistencyLevel::Finalized => 32,
+    };
+}
+
+/// Event logged for a message written by `write_message`, carrying the payload so that messages
+/// can be observed even after their account has been reused.
+fn message_posted(message: &Pubkey, data: &MessageData) -> Event {
+    Event::MessagePosted {
+        message: *message,
+        emitter: Pubkey::new_from_array(data.emitter_address),
+        sequence: data.sequence,
+        nonce: data.nonce,
+        consistency_level: data.consistency_level,
+        submission_time: data.submission_time,
+        payload: data.payload.clone(),
+    }
+}
@@ -6,6 +6,7 @@ use borsh::{
 };
 use solana_program::{
     self,
+    pubkey::Pubkey,
     sysvar::clock::Clock,
 };
 
@@ -13,16 +14,28 @@ use crate::{
     accounts::{
         Bridge,
         GuardianSet,
+        GuardianSetData,
         GuardianSetDerivationData,
+        MessageData,
         PostedVAA,
+        PostedVAAData,
         PostedVAADerivationData,
+        Quorum,
+        QuorumData,
+        QuorumDerivationData,
         SignatureSet,
     },
+    api::{
+        close::record_rent_payer,
+        verify_signature::verify_secp_signatures,
+    },
     error::Error::{
         GuardianSetMismatch,
+        InvalidHash,
         PostVAAConsensusFailed,
         PostVAAGuardianSetExpired,
     },
+    Event,
 };
 use byteorder::{
     BigEndian,
@@ -122,39 +135,174 @@ pub fn post_vaa(ctx: &ExecutionContext, accs: &mut PostVAA, vaa: PostVAAData) ->
 
     // Count the number of signatures currently present.
     let signature_count: usize = accs.signature_set.signatures.iter().filter(|v| **v).count();
+    if signature_count < required_consensus_count(ctx, &accs.guardian_set)? {
+        return Err(PostVAAConsensusFailed.into());
+    }
+
+    // Persist VAA data
+    write_vaa(&mut accs.message, vaa, *accs.signature_set.info().key);
+    accs.message
+        .create(&msg_derivation, ctx, accs.payer.key, Exempt)?;
+    record_rent_payer(ctx, accs.payer.key, accs.message.info().key)?;
+
+    vaa_posted(
+        accs.message.info().key,
+        accs.signature_set.hash,
+        accs.guardian_set.index,
+        &accs.message,
+    )
+    .emit();
 
-    // Calculate how many signatures are required to reach consensus. This calculation is in
-    // expanded form to ease auditing.
-    let required_consensus_count = {
-        let len = accs.guardian_set.keys.len();
-        // Fixed point number transformation with one decimal to deal with rounding.
-        let len = (len * 10) / 3;
-        // Multiplication by two to get a 2/3 quorum.
-        let len = len * 2;
-        // Division to bring number back into range.
-        len / 10 + 1
+    Ok(())
+}
+
+#[derive(FromAccounts)]
+pub struct VerifyAndPostVAA<'b> {
+    /// Information about the current guardian set.
+    pub guardian_set: GuardianSet<'b, { AccountState::Initialized }>,
+
+    /// Bridge Info
+    pub bridge_info: Bridge<'b, { AccountState::Initialized }>,
+
+    /// Message the VAA is associated with.
+    pub message: Mut<PostedVAA<'b, { AccountState::MaybeInitialized }>>,
+
+    /// Account used to pay for auxillary instructions.
+    pub payer: Mut<Signer<Info<'b>>>,
+
+    /// Clock used for timestamping.
+    pub clock: Sysvar<'b, Clock>,
+
+    /// Instruction reflection account (special sysvar)
+    pub instruction_acc: Info<'b>,
+}
+
+impl<'b> InstructionContext<'b> for VerifyAndPostVAA<'b> {
+}
+
+#[derive(Default, BorshSerialize, BorshDeserialize)]
+pub struct VerifyAndPostVAAData {
+    pub vaa: PostVAAData,
+
+    /// Guardian index and position of its signature in the secp256k1 instruction, for each
+    /// signature. Ordered by strictly ascending guardian index.
+    pub signers: Vec<(u8, u8)>,
+
+    /// Index of the secp256k1 instruction holding the signatures, which must come earlier in the
+    /// transaction. Defaults to the instruction immediately before this one.
+    pub secp_instruction_index: Option<u8>,
+}
+
+/// Verify the signatures of a VAA and post it in a single instruction, without a signature set.
+/// All signatures need to fit into the secp256k1 instruction of a single transaction, so this is
+/// only usable with small guardian sets.
+pub fn verify_and_post_vaa(
+    ctx: &ExecutionContext,
+    accs: &mut VerifyAndPostVAA,
+    data: VerifyAndPostVAAData,
+) -> Result<()> {
+    let vaa = data.vaa;
+    let body_hash = body_hash(&vaa)?;
+    let msg_derivation = PostedVAADerivationData {
+        payload_hash: body_hash.to_vec(),
     };
 
-    if signature_count < required_consensus_count {
+    accs.message
+        .verify_derivation(ctx.program_id, &msg_derivation)?;
+    accs.guardian_set
+        .verify_derivation(ctx.program_id, &(&vaa).into())?;
+
+    if accs.message.is_initialized() {
+        return Ok(());
+    }
+
+    check_active(&accs.guardian_set, &accs.clock)?;
+
+    let (msg_hash, signers) = verify_secp_signatures(
+        &accs.instruction_acc,
+        data.secp_instruction_index,
+        &data.signers,
+        &accs.guardian_set,
+    )?;
+    if msg_hash != body_hash {
+        return Err(InvalidHash.into());
+    }
+
+    // Signers are checked to be in ascending guardian order, so each guardian is counted once.
+    if signers.len() < required_consensus_count(ctx, &accs.guardian_set)? {
         return Err(PostVAAConsensusFailed.into());
     }
 
-    // Persist VAA data
-    accs.message.nonce = vaa.nonce;
-    accs.message.emitter_chain = vaa.emitter_chain;
-    accs.message.emitter_address = vaa.emitter_address;
-    accs.message.sequence = vaa.sequence;
-    accs.message.payload = vaa.payload;
-    accs.message.consistency_level = vaa.consistency_level;
-    accs.message.vaa_version = vaa.version;
-    accs.message.vaa_time = vaa.timestamp;
-    accs.message.vaa_signature_account = *accs.signature_set.info().key;
+    write_vaa(&mut accs.message, vaa, Pubkey::default());
     accs.message
         .create(&msg_derivation, ctx, accs.payer.key, Exempt)?;
+    record_rent_payer(ctx, accs.payer.key, accs.message.info().key)?;
+
+    vaa_posted(
+        accs.message.info().key,
+        body_hash,
+        accs.guardian_set.index,
+        &accs.message,
+    )
+    .emit();
 
     Ok(())
 }
 
+/// Calculate how many signatures are required to reach consensus. Governance can set a quorum for
+/// the guardian set, which is then read from the trailing accounts of the instruction so that
+/// clients unaware of it keep the legacy account list and are held to the default.
+fn required_consensus_count(
+    ctx: &ExecutionContext,
+    guardian_set: &GuardianSetData,
+) -> Result<usize> {
+    let key = Quorum::<'_, { AccountState::Initialized }>::key(
+        &QuorumDerivationData {
+            guardian_set_index: guardian_set.index,
+        },
+        ctx.program_id,
+    );
+    let info = match ctx
+        .accounts
+        .iter()
+        .find(|info| *info.key == key && info.owner == ctx.program_id)
+    {
+        Some(info) => info,
+        None => return Ok(default_consensus_count(guardian_set)),
+    };
+
+    match QuorumData::try_from_slice(&info.data.borrow())?.quorum {
+        0 => Ok(default_consensus_count(guardian_set)),
+        quorum => Ok(quorum as usize),
+    }
+}
+
+/// Calculate the default number of signatures required to reach consensus, 2/3+1 of the guardians.
+/// The calculation is in expanded form to ease auditing.
+pub(crate) fn default_consensus_count(guardian_set: &GuardianSetData) -> usize {
+    let len = guardian_set.keys.len();
+    // Fixed point number transformation with one decimal to deal with rounding.
+    let len = (len * 10) / 3;
+    // Multiplication by two to get a 2/3 quorum.
+    let len = len * 2;
+    // Division to bring number back into range.
+    len / 10 + 1
+}
+
+/// Write a verified VAA to its posted VAA account. VAAs posted without a signature set record the
+/// default key as their signature account.
+fn write_vaa(message: &mut PostedVAAData, vaa: PostVAAData, signature_set: Pubkey) {
+    message.nonce = vaa.nonce;
+    message.emitter_chain = vaa.emitter_chain;
+    message.emitter_address = vaa.emitter_address;
+    message.sequence = vaa.sequence;
+    message.payload = vaa.payload;
+    message.consistency_level = vaa.consistency_level;
+    message.vaa_version = vaa.version;
+    message.vaa_time = vaa.timestamp;
+    message.vaa_signature_account = signature_set;
+}
+
 /// A guardian set must not have expired.
 #[inline(always)]
 fn check_active<'r>(
@@ -191,6 +339,16 @@ fn check_integrity<'r>(
     vaa: &PostVAAData,
     signatures: &SignatureSet<'r, { AccountState::Initialized }>,
 ) -> Result<()> {
+    // The hash of the body is expected to be the same as the hash currently stored in the
+    // signature account, binding that set of signatures to this VAA.
+    if signatures.hash != body_hash(vaa)? {
+        return Err(ProgramError::InvalidAccountData.into());
+    }
+    Ok(())
+}
+
+/// Hash the body of a VAA, which is what guardians sign.
+pub(crate) fn body_hash(vaa: &PostVAAData) -> Result<[u8; 32]> {
     // Serialize the VAA body into an array of bytes.
     let body = {
         let mut v = Cursor::new(Vec::new());
@@ -204,17 +362,25 @@ fn check_integrity<'r>(
         v.into_inner()
     };
 
-    // Hash this body, which is expected to be the same as the hash currently stored in the
-    // signature account, binding that set of signatures to this VAA.
-    let body_hash: [u8; 32] = {
-        let mut h = sha3::Keccak256::default();
-        h.write(body.as_slice())
-            .map_err(|_| ProgramError::InvalidArgument)?;
-        h.finalize().into()
-    };
+    let mut h = sha3::Keccak256::default();
+    h.write(body.as_slice())
+        .map_err(|_| ProgramError::InvalidArgument)?;
+    Ok(h.finalize().into())
+}
 
-    if signatures.hash != body_hash {
-        return Err(ProgramError::InvalidAccountData.into());
+/// Event logged once a VAA has been written to its posted VAA account.
+fn vaa_posted(
+    posted_vaa: &Pubkey,
+    hash: [u8; 32],
+    guardian_set_index: u32,
+    message: &MessageData,
+) -> Event {
+    Event::VAAPosted {
+        posted_vaa: *posted_vaa,
+        hash,
+        guardian_set_index,
+        emitter_chain: message.emitter_chain,
+        emitter_address: message.emitter_address,
+        sequence: message.sequence,
     }
-    Ok(())
 }
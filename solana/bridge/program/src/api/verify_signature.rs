@@ -1,6 +1,7 @@
 use solitaire::*;
 
 use crate::{
+    api::close::record_rent_payer,
     error::Error::{
         GuardianSetMismatch,
         InstructionAtWrongIndex,
@@ -8,9 +9,9 @@ use crate::{
         InvalidSecpInstruction,
     },
     GuardianSet,
+    GuardianSetData,
     GuardianSetDerivationData,
     SignatureSet,
-    MAX_LEN_GUARDIAN_KEYS,
 };
 use byteorder::ByteOrder;
 use solana_program::program_error::ProgramError;
@@ -18,6 +19,7 @@ use solitaire::{
     processors::seeded::Seeded,
     CreationLamports::Exempt,
 };
+use std::io::Write;
 
 #[derive(FromAccounts)]
 pub struct VerifySignatures<'b> {
@@ -45,18 +47,86 @@ impl From<&VerifySignatures<'_>> for GuardianSetDerivationData {
     }
 }
 
-#[derive(Default, BorshSerialize, BorshDeserialize)]
+/// Number of guardians addressed by the fixed size signer array of the legacy encoding.
+const LEGACY_SIGNERS_LEN: usize = 19;
+
+/// Leading byte of the variable length encoding. Legacy signer arrays can never start with it, as
+/// their entries are either -1 or the position of one of at most 19 signatures.
+const SIGNER_LIST_TAG: u8 = 0x80;
+
+#[derive(Default)]
 pub struct VerifySignaturesData {
-    /// instruction indices of signers (-1 for missing)
-    pub signers: [i8; MAX_LEN_GUARDIAN_KEYS],
+    /// Guardian index and position of its signature in the secp256k1 instruction, for each
+    /// signature. Ordered by strictly ascending guardian index.
+    pub signers: Vec<(u8, u8)>,
+
+    /// Index of the secp256k1 instruction holding the signatures, which must come earlier in the
+    /// transaction. Defaults to the instruction immediately before this one.
+    pub secp_instruction_index: Option<u8>,
 }
 
-/// SigInfo contains metadata about signers in a VerifySignature ix
-struct SigInfo {
-    /// index of the signer in the guardianset
-    signer_index: u8,
-    /// index of the signature in the secp instruction
-    sig_index: u8,
+impl VerifySignaturesData {
+    /// Whether the signers can be expressed by the legacy fixed size signer array.
+    fn is_legacy(&self) -> bool {
+        self.signers.iter().all(|(guardian, signature)| {
+            (*guardian as usize) < LEGACY_SIGNERS_LEN && *signature <= i8::MAX as u8
+        }) && self.signers.windows(2).all(|w| w[0].0 < w[1].0)
+    }
+}
+
+impl BorshSerialize for VerifySignaturesData {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        if !self.is_legacy() {
+            BorshSerialize::serialize(&SIGNER_LIST_TAG, writer)?;
+            BorshSerialize::serialize(&self.secp_instruction_index, writer)?;
+            BorshSerialize::serialize(&self.signers, writer)?;
+            return Ok(());
+        }
+
+        // Small guardian sets keep the legacy encoding, understood by every deployed version.
+        let mut signers = [-1i8; LEGACY_SIGNERS_LEN];
+        for (guardian, signature) in &self.signers {
+            signers[*guardian as usize] = *signature as i8;
+        }
+        BorshSerialize::serialize(&signers, writer)?;
+
+        // Omit a missing index so the instruction data matches that of older clients.
+        if let Some(index) = self.secp_instruction_index {
+            BorshSerialize::serialize(&index, writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl BorshDeserialize for VerifySignaturesData {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        if buf.first() == Some(&SIGNER_LIST_TAG) {
+            *buf = &buf[1..];
+            let secp_instruction_index = BorshDeserialize::deserialize(buf)?;
+            let signers = BorshDeserialize::deserialize(buf)?;
+            return Ok(VerifySignaturesData {
+                signers,
+                secp_instruction_index,
+            });
+        }
+
+        // Legacy clients send the signature position of each guardian, -1 if it did not sign.
+        let legacy: [i8; LEGACY_SIGNERS_LEN] = BorshDeserialize::deserialize(buf)?;
+        let signers = legacy
+            .iter()
+            .enumerate()
+            .filter(|(_, signature)| **signature != -1)
+            .map(|(guardian, signature)| (guardian as u8, *signature as u8))
+            .collect();
+        let secp_instruction_index = match buf.len() {
+            0 => None,
+            _ => Some(BorshDeserialize::deserialize(buf)?),
+        };
+        Ok(VerifySignaturesData {
+            signers,
+            secp_instruction_index,
+        })
+    }
 }
 
 struct SecpInstructionPart<'a> {
@@ -73,34 +143,75 @@ pub fn verify_signatures(
     accs.guardian_set
         .verify_derivation(ctx.program_id, &(&*accs).into())?;
 
-    let sig_infos: Vec<SigInfo> = data
-        .signers
-        .iter()
-        .enumerate()
-        .filter_map(|(i, p)| {
-            if *p == -1 {
-                return None;
-            }
-
-            return Some(SigInfo {
-                sig_index: *p as u8,
-                signer_index: i as u8,
-            });
-        })
-        .collect();
-
-    let current_instruction = solana_program::sysvar::instructions::load_current_index_checked(
+    let (msg_hash, signers) = verify_secp_signatures(
         &accs.instruction_acc,
+        data.secp_instruction_index,
+        &data.signers,
+        &accs.guardian_set,
     )?;
-    if current_instruction == 0 {
-        return Err(InstructionAtWrongIndex.into());
+
+    if !accs.signature_set.is_initialized() {
+        accs.signature_set.signatures = vec![false; accs.guardian_set.keys.len()];
+        accs.signature_set.guardian_set_index = accs.guardian_set.index;
+        accs.signature_set.hash = msg_hash;
+
+        let size = accs.signature_set.size();
+        let ix = solana_program::system_instruction::create_account(
+            accs.payer.key,
+            accs.signature_set.info().key,
+            Exempt.amount(size),
+            size as u64,
+            ctx.program_id,
+        );
+        solana_program::program::invoke(&ix, ctx.accounts)?;
+        record_rent_payer(ctx, accs.payer.key, accs.signature_set.info().key)?;
+    } else {
+        // If the account already existed, check that the parameters match
+        if accs.signature_set.guardian_set_index != accs.guardian_set.index {
+            return Err(GuardianSetMismatch.into());
+        }
+
+        if accs.signature_set.hash != msg_hash {
+            return Err(InvalidHash.into());
+        }
     }
 
-    // The previous ix must be a secp verification instruction
-    let secp_ix_index = (current_instruction - 1) as u8;
+    // Write sigs of checked addresses into sig_state. Overwritten content should be zeros except
+    // double signs by the signer or harmless replays.
+    for signer in signers {
+        accs.signature_set.signatures[signer as usize] = true;
+    }
+
+    Ok(())
+}
+
+/// Check the signatures verified by a secp256k1 instruction earlier in the transaction against the
+/// guardian set. `signers` pairs each signing guardian with the position of its signature in the
+/// secp256k1 instruction. Returns the signed hash along with the indices of the guardians whose
+/// signatures were verified, each guardian appearing at most once.
+pub(crate) fn verify_secp_signatures(
+    instruction_acc: &Info,
+    secp_instruction_index: Option<u8>,
+    signers: &[(u8, u8)],
+    guardian_set: &GuardianSetData,
+) -> Result<([u8; 32], Vec<u8>)> {
+    // Strictly ascending guardian indices guarantee a guardian cannot be counted twice.
+    if signers.windows(2).any(|w| w[0].0 >= w[1].0) {
+        return Err(ProgramError::InvalidArgument.into());
+    }
+
+    let current_instruction =
+        solana_program::sysvar::instructions::load_current_index_checked(instruction_acc)?;
+
+    // The secp instruction must precede this one, by default immediately.
+    let secp_ix_index = match secp_instruction_index {
+        Some(index) if (index as u16) < current_instruction => index,
+        None if current_instruction > 0 => (current_instruction - 1) as u8,
+        _ => return Err(InstructionAtWrongIndex.into()),
+    };
     let secp_ix = solana_program::sysvar::instructions::load_instruction_at_checked(
         secp_ix_index as usize,
-        &accs.instruction_acc,
+        instruction_acc,
     )
     .map_err(|_| ProgramError::InvalidAccountData)?;
 
@@ -153,7 +264,7 @@ pub fn verify_signatures(
         });
     }
 
-    if sig_infos.len() != secp_ixs.len() {
+    if signers.len() != secp_ixs.len() {
         return Err(ProgramError::InvalidArgument.into());
     }
 
@@ -170,50 +281,25 @@ pub fn verify_signatures(
     let mut msg_hash: [u8; 32] = [0u8; 32];
     msg_hash.copy_from_slice(message);
 
-    if !accs.signature_set.is_initialized() {
-        accs.signature_set.signatures = vec![false; accs.guardian_set.keys.len()];
-        accs.signature_set.guardian_set_index = accs.guardian_set.index;
-        accs.signature_set.hash = msg_hash;
-
-        let size = accs.signature_set.size();
-        let ix = solana_program::system_instruction::create_account(
-            accs.payer.key,
-            accs.signature_set.info().key,
-            Exempt.amount(size),
-            size as u64,
-            ctx.program_id,
-        );
-        solana_program::program::invoke(&ix, ctx.accounts)?;
-    } else {
-        // If the account already existed, check that the parameters match
-        if accs.signature_set.guardian_set_index != accs.guardian_set.index {
-            return Err(GuardianSetMismatch.into());
-        }
-
-        if accs.signature_set.hash != msg_hash {
-            return Err(InvalidHash.into());
-        }
-    }
-
-    // Write sigs of checked addresses into sig_state
-    for s in sig_infos {
-        if s.signer_index > accs.guardian_set.num_guardians() {
+    // Check the address of each signature against the guardian that claims it.
+    let mut verified = Vec::with_capacity(signers.len());
+    for (signer_index, sig_index) in signers.iter().copied() {
+        if signer_index as usize >= guardian_set.keys.len() {
             return Err(ProgramError::InvalidArgument.into());
         }
 
-        if s.sig_index + 1 > sig_len {
+        if sig_index >= sig_len {
             return Err(ProgramError::InvalidArgument.into());
         }
 
-        let key = accs.guardian_set.keys[s.signer_index as usize];
+        let key = guardian_set.keys[signer_index as usize];
         // Check key in ix
-        if key != secp_ixs[s.sig_index as usize].address {
+        if key != secp_ixs[sig_index as usize].address {
             return Err(ProgramError::InvalidArgument.into());
         }
 
-        // Overwritten content should be zeros except double signs by the signer or harmless replays
-        accs.signature_set.signatures[s.signer_index as usize] = true;
+        verified.push(signer_index);
     }
 
-    Ok(())
+    Ok((msg_hash, verified))
 }
@@ -23,6 +23,12 @@ pub enum Error {
     PostVAAGuardianSetExpired,
     TooManyGuardians,
     VAAAlreadyExecuted,
+    InvalidPayer,
+    PostedVAANotExpired,
+    MessageTooLarge,
+    InvalidQuorum,
+    MissingEmitterFee,
+    InvalidEmitterFee,
 }
 
 /// Errors thrown by the program will bubble up to the solitaire wrapper, which needs a way to
@@ -27,22 +27,37 @@ use crate::{
         Bridge,
         Claim,
         ClaimDerivationData,
+        EmitterFee,
+        EmitterFeeDerivationData,
         FeeCollector,
         GuardianSet,
         GuardianSetDerivationData,
         PostedVAA,
         PostedVAADerivationData,
+        PostedMessageUnreliable,
+        PostedMessageUnreliableDerivationData,
+        PostedVAAExpiry,
+        Quorum,
+        QuorumDerivationData,
+        RentPayer,
+        RentPayerDerivationData,
         Sequence,
         SequenceDerivationData,
     },
     types::ConsistencyLevel,
+    ClosePostedVAAData,
+    CloseSignatureSetData,
     InitializeData,
     PostMessageData,
     PostVAAData,
+    SetEmitterFeeData,
     SetFeesData,
+    SetPostedVAAExpiryData,
+    SetQuorumData,
     TransferFeesData,
     UpgradeContractData,
     UpgradeGuardianSetData,
+    VerifyAndPostVAAData,
     VerifySignaturesData,
     CHAIN_ID_SOLANA,
 };
@@ -129,6 +144,90 @@ pub fn post_message(
     })
 }
 
+/// Account to append to the post_message and post_message_unreliable instructions of an emitter
+/// governance has set a fee for, which can be left out for any other emitter.
+pub fn emitter_fee_account(program_id: Pubkey, emitter: Pubkey) -> AccountMeta {
+    let emitter_fee = EmitterFee::<'_, { AccountState::MaybeInitialized }>::key(
+        &EmitterFeeDerivationData {
+            emitter_key: &emitter,
+        },
+        &program_id,
+    );
+    AccountMeta::new_readonly(emitter_fee, false)
+}
+
+/// RentPayer account of a posted VAA or signature set. Instructions creating either record their
+/// payer in it when it is passed, which is required to close the account later.
+pub fn rent_payer_account(program_id: Pubkey, account: Pubkey) -> AccountMeta {
+    let rent_payer = RentPayer::<'_, { AccountState::MaybeInitialized }>::key(
+        &RentPayerDerivationData {
+            account_key: &account,
+        },
+        &program_id,
+    );
+    AccountMeta::new(rent_payer, false)
+}
+
+/// Quorum account of a guardian set, appended to the instructions posting VAAs signed by it. VAAs
+/// posted without it need the default number of signatures even if governance set a lower one.
+pub fn quorum_account(program_id: Pubkey, guardian_set_index: u32) -> AccountMeta {
+    let quorum = Quorum::<'_, { AccountState::MaybeInitialized }>::key(
+        &QuorumDerivationData { guardian_set_index },
+        &program_id,
+    );
+    AccountMeta::new_readonly(quorum, false)
+}
+
+pub fn post_message_unreliable(
+    program_id: Pubkey,
+    payer: Pubkey,
+    emitter: Pubkey,
+    nonce: u32,
+    payload: Vec<u8>,
+    commitment: ConsistencyLevel,
+) -> solitaire::Result<Instruction> {
+    let bridge = Bridge::<'_, { AccountState::Uninitialized }>::key(None, &program_id);
+    let fee_collector = FeeCollector::<'_>::key(None, &program_id);
+    let sequence = Sequence::<'_>::key(
+        &SequenceDerivationData {
+            emitter_key: &emitter,
+        },
+        &program_id,
+    );
+    let message = PostedMessageUnreliable::<'_, { AccountState::MaybeInitialized }>::key(
+        &PostedMessageUnreliableDerivationData {
+            emitter_key: &emitter,
+        },
+        &program_id,
+    );
+
+    Ok(Instruction {
+        program_id,
+
+        accounts: vec![
+            AccountMeta::new(bridge, false),
+            AccountMeta::new(message, false),
+            AccountMeta::new_readonly(emitter, true),
+            AccountMeta::new(sequence, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new(fee_collector, false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+
+        data: (
+            crate::instruction::Instruction::PostMessageUnreliable,
+            PostMessageData {
+                nonce,
+                payload,
+                consistency_level: commitment,
+            },
+        )
+            .try_to_vec()?,
+    })
+}
+
 pub fn verify_signatures(
     program_id: Pubkey,
     payer: Pubkey,
@@ -153,6 +252,7 @@ pub fn verify_signatures(
             AccountMeta::new_readonly(sysvar::instructions::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            rent_payer_account(program_id, signature_set),
         ],
 
         data: (crate::instruction::Instruction::VerifySignatures, data).try_to_vec()?,
@@ -192,6 +292,8 @@ pub fn post_vaa(
             AccountMeta::new_readonly(sysvar::clock::id(), false),
             AccountMeta::new_readonly(sysvar::rent::id(), false),
             AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            rent_payer_account(program_id, message),
+            quorum_account(program_id, vaa.guardian_set_index),
         ],
 
         data: (crate::instruction::Instruction::PostVAA, vaa)
@@ -200,6 +302,48 @@ pub fn post_vaa(
     }
 }
 
+pub fn verify_and_post_vaa(
+    program_id: Pubkey,
+    payer: Pubkey,
+    data: VerifyAndPostVAAData,
+) -> Instruction {
+    let bridge = Bridge::<'_, { AccountState::Uninitialized }>::key(None, &program_id);
+    let guardian_set = GuardianSet::<'_, { AccountState::Uninitialized }>::key(
+        &GuardianSetDerivationData {
+            index: data.vaa.guardian_set_index,
+        },
+        &program_id,
+    );
+
+    let msg_derivation_data = &PostedVAADerivationData {
+        payload_hash: hash_vaa(&data.vaa).to_vec(),
+    };
+
+    let message =
+        PostedVAA::<'_, { AccountState::MaybeInitialized }>::key(&msg_derivation_data, &program_id);
+
+    Instruction {
+        program_id,
+
+        accounts: vec![
+            AccountMeta::new_readonly(guardian_set, false),
+            AccountMeta::new_readonly(bridge, false),
+            AccountMeta::new(message, false),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            rent_payer_account(program_id, message),
+            quorum_account(program_id, data.vaa.guardian_set_index),
+        ],
+
+        data: (crate::instruction::Instruction::VerifyAndPostVAA, data)
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
 pub fn upgrade_contract(
     program_id: Pubkey,
     payer: Pubkey,
@@ -382,6 +526,189 @@ pub fn transfer_fees(
     }
 }
 
+pub fn set_posted_vaa_expiry(
+    program_id: Pubkey,
+    payer: Pubkey,
+    message: Pubkey,
+    emitter: Pubkey,
+    sequence: u64,
+) -> Instruction {
+    let bridge = Bridge::<'_, { AccountState::Uninitialized }>::key(None, &program_id);
+    let claim = Claim::<'_, { AccountState::Uninitialized }>::key(
+        &ClaimDerivationData {
+            emitter_address: emitter.to_bytes(),
+            emitter_chain: CHAIN_ID_SOLANA,
+            sequence,
+        },
+        &program_id,
+    );
+    let expiry = PostedVAAExpiry::<'_, { AccountState::Uninitialized }>::key(None, &program_id);
+
+    Instruction {
+        program_id,
+
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(bridge, false),
+            AccountMeta::new_readonly(message, false),
+            AccountMeta::new(claim, false),
+            AccountMeta::new(expiry, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+
+        data: (
+            crate::instruction::Instruction::SetPostedVAAExpiry,
+            SetPostedVAAExpiryData {},
+        )
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+pub fn set_quorum(
+    program_id: Pubkey,
+    payer: Pubkey,
+    message: Pubkey,
+    emitter: Pubkey,
+    guardian_set_index: u32,
+    sequence: u64,
+) -> Instruction {
+    let bridge = Bridge::<'_, { AccountState::Uninitialized }>::key(None, &program_id);
+    let claim = Claim::<'_, { AccountState::Uninitialized }>::key(
+        &ClaimDerivationData {
+            emitter_address: emitter.to_bytes(),
+            emitter_chain: CHAIN_ID_SOLANA,
+            sequence,
+        },
+        &program_id,
+    );
+    let guardian_set = GuardianSet::<'_, { AccountState::Initialized }>::key(
+        &GuardianSetDerivationData {
+            index: guardian_set_index,
+        },
+        &program_id,
+    );
+    let quorum = Quorum::<'_, { AccountState::MaybeInitialized }>::key(
+        &QuorumDerivationData { guardian_set_index },
+        &program_id,
+    );
+
+    Instruction {
+        program_id,
+
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(bridge, false),
+            AccountMeta::new_readonly(message, false),
+            AccountMeta::new(claim, false),
+            AccountMeta::new_readonly(guardian_set, false),
+            AccountMeta::new(quorum, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+
+        data: (crate::instruction::Instruction::SetQuorum, SetQuorumData {})
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+pub fn set_emitter_fee(
+    program_id: Pubkey,
+    payer: Pubkey,
+    message: Pubkey,
+    emitter: Pubkey,
+    sequence: u64,
+    fee_emitter: Pubkey,
+) -> Instruction {
+    let bridge = Bridge::<'_, { AccountState::Uninitialized }>::key(None, &program_id);
+    let claim = Claim::<'_, { AccountState::Uninitialized }>::key(
+        &ClaimDerivationData {
+            emitter_address: emitter.to_bytes(),
+            emitter_chain: CHAIN_ID_SOLANA,
+            sequence,
+        },
+        &program_id,
+    );
+    let emitter_fee = EmitterFee::<'_, { AccountState::MaybeInitialized }>::key(
+        &EmitterFeeDerivationData {
+            emitter_key: &fee_emitter,
+        },
+        &program_id,
+    );
+    let fee_sequence = Sequence::<'_>::key(
+        &SequenceDerivationData {
+            emitter_key: &fee_emitter,
+        },
+        &program_id,
+    );
+
+    Instruction {
+        program_id,
+
+        accounts: vec![
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(bridge, false),
+            AccountMeta::new_readonly(message, false),
+            AccountMeta::new(claim, false),
+            AccountMeta::new(emitter_fee, false),
+            AccountMeta::new(fee_sequence, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        ],
+
+        data: (crate::instruction::Instruction::SetEmitterFee, SetEmitterFeeData {})
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+pub fn close_signature_set(
+    program_id: Pubkey,
+    payer: Pubkey,
+    signature_set: Pubkey,
+    posted_vaa: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+
+        accounts: vec![
+            AccountMeta::new(payer, false),
+            AccountMeta::new(signature_set, false),
+            AccountMeta::new_readonly(posted_vaa, false),
+            rent_payer_account(program_id, signature_set),
+        ],
+
+        data: (
+            crate::instruction::Instruction::CloseSignatureSet,
+            CloseSignatureSetData {},
+        )
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+pub fn close_posted_vaa(program_id: Pubkey, payer: Pubkey, posted_vaa: Pubkey) -> Instruction {
+    let expiry = PostedVAAExpiry::<'_, { AccountState::Uninitialized }>::key(None, &program_id);
+
+    Instruction {
+        program_id,
+
+        accounts: vec![
+            AccountMeta::new(payer, false),
+            AccountMeta::new_readonly(expiry, false),
+            AccountMeta::new(posted_vaa, false),
+            AccountMeta::new_readonly(sysvar::clock::id(), false),
+            rent_payer_account(program_id, posted_vaa),
+        ],
+
+        data: (
+            crate::instruction::Instruction::ClosePostedVAA,
+            ClosePostedVAAData {},
+        )
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
 // Convert a full VAA structure into the serialization of its unique components, this structure is
 // what is hashed and verified by Guardians.
 pub fn serialize_vaa(vaa: &PostVAAData) -> Vec<u8> {
@@ -5,7 +5,8 @@
 
 use solitaire::*;
 
-pub const MAX_LEN_GUARDIAN_KEYS: usize = 19;
+/// Guardian set sizes are encoded as a single byte in governance payloads.
+pub const MAX_LEN_GUARDIAN_KEYS: usize = u8::MAX as usize;
 pub const CHAIN_ID_SOLANA: u16 = 1;
 
 #[cfg(feature = "no-entrypoint")]
@@ -26,15 +27,28 @@ pub use accounts::{
     Claim,
     ClaimData,
     ClaimDerivationData, 
+    EmitterFee,
+    EmitterFeeData,
+    EmitterFeeDerivationData,
     FeeCollector,
     GuardianSet,
     GuardianSetData,
     GuardianSetDerivationData,
     PostedMessage,
     PostedMessageData,
+    PostedMessageUnreliable,
+    PostedMessageUnreliableData,
     MessageData,
     PostedVAA,
     PostedVAAData,
+    PostedVAAExpiry,
+    PostedVAAExpiryData,
+    Quorum,
+    QuorumData,
+    QuorumDerivationData,
+    RentPayer,
+    RentPayerData,
+    RentPayerDerivationData,
     Sequence,
     SequenceTracker,
     SequenceDerivationData,
@@ -44,22 +58,40 @@ pub use accounts::{
 
 pub mod api;
 pub use api::{
+    close_posted_vaa,
+    close_signature_set,
     initialize,
     post_message,
+    post_message_unreliable,
     post_vaa,
+    set_emitter_fee,
     set_fees,
+    set_posted_vaa_expiry,
+    set_quorum,
     transfer_fees,
     upgrade_contract,
     upgrade_guardian_set,
+    verify_and_post_vaa,
     verify_signatures,
+    ClosePostedVAA,
+    ClosePostedVAAData,
+    CloseSignatureSet,
+    CloseSignatureSetData,
     Initialize,
     InitializeData,
     PostMessage,
     PostMessageData,
+    PostMessageUnreliable,
     PostVAA,
     PostVAAData,
+    SetEmitterFee,
+    SetEmitterFeeData,
     SetFees,
     SetFeesData,
+    SetPostedVAAExpiry,
+    SetPostedVAAExpiryData,
+    SetQuorum,
+    SetQuorumData,
     Signature,
     TransferFees,
     TransferFeesData,
@@ -68,6 +100,8 @@ pub use api::{
     UpgradeContractData,
     UpgradeGuardianSet,
     UpgradeGuardianSetData,
+    VerifyAndPostVAA,
+    VerifyAndPostVAAData,
     VerifySignatures,
     VerifySignaturesData,
 };
@@ -76,6 +110,10 @@ pub mod error;
 pub mod types;
 pub mod vaa;
 
+pub use bridge_events::{
+    self as events,
+    Event,
+};
 pub use vaa::{
     DeserializeGovernancePayload,
     DeserializePayload,
@@ -93,4 +131,11 @@ solitaire! {
     UpgradeContract(UpgradeContractData)        => upgrade_contract,
     UpgradeGuardianSet(UpgradeGuardianSetData)  => upgrade_guardian_set,
     VerifySignatures(VerifySignaturesData)      => verify_signatures,
+    CloseSignatureSet(CloseSignatureSetData)    => close_signature_set,
+    ClosePostedVAA(ClosePostedVAAData)          => close_posted_vaa,
+    SetPostedVAAExpiry(SetPostedVAAExpiryData)  => set_posted_vaa_expiry,
+    PostMessageUnreliable(PostMessageData)      => post_message_unreliable,
+    VerifyAndPostVAA(VerifyAndPostVAAData)      => verify_and_post_vaa,
+    SetQuorum(SetQuorumData)                    => set_quorum,
+    SetEmitterFee(SetEmitterFeeData)            => set_emitter_fee,
 }
@@ -233,3 +233,155 @@ impl SerializeGovernancePayload for GovernancePayloadTransferFees {
 
 impl DeserializeGovernancePayload for GovernancePayloadTransferFees {
 }
+
+pub struct GovernancePayloadSetPostedVAAExpiry {
+    // Seconds after which posted VAAs can be closed, or 0 to never expire them
+    pub expiration_time: u32,
+}
+
+impl SerializePayload for GovernancePayloadSetPostedVAAExpiry {
+    fn serialize<W: Write>(&self, v: &mut W) -> std::result::Result<(), SolitaireError> {
+        use byteorder::WriteBytesExt;
+        v.write_u32::<BigEndian>(self.expiration_time)?;
+        Ok(())
+    }
+}
+
+impl DeserializePayload for GovernancePayloadSetPostedVAAExpiry
+where
+    Self: DeserializeGovernancePayload,
+{
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, SolitaireError> {
+        let mut c = Cursor::new(buf);
+        Self::check_governance_header(&mut c)?;
+
+        let expiration_time = c.read_u32::<BigEndian>()?;
+
+        if c.position() != c.into_inner().len() as u64 {
+            return Err(InvalidAccountData.into());
+        }
+
+        Ok(GovernancePayloadSetPostedVAAExpiry { expiration_time })
+    }
+}
+
+impl SerializeGovernancePayload for GovernancePayloadSetPostedVAAExpiry {
+    const MODULE: &'static str = "Core";
+    const ACTION: u8 = 5;
+}
+
+impl DeserializeGovernancePayload for GovernancePayloadSetPostedVAAExpiry {
+}
+
+pub struct GovernancePayloadSetQuorum {
+    // Index of the guardian set to change, which must be the active one
+    pub guardian_set_index: u32,
+
+    // Number of signatures required to reach consensus, at most the default 2/3+1, or 0 for it
+    pub quorum: u8,
+}
+
+impl SerializePayload for GovernancePayloadSetQuorum {
+    fn serialize<W: Write>(&self, v: &mut W) -> std::result::Result<(), SolitaireError> {
+        use byteorder::WriteBytesExt;
+        v.write_u32::<BigEndian>(self.guardian_set_index)?;
+        v.write_u8(self.quorum)?;
+        Ok(())
+    }
+}
+
+impl DeserializePayload for GovernancePayloadSetQuorum
+where
+    Self: DeserializeGovernancePayload,
+{
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, SolitaireError> {
+        let mut c = Cursor::new(buf);
+        Self::check_governance_header(&mut c)?;
+
+        let guardian_set_index = c.read_u32::<BigEndian>()?;
+        let quorum = c.read_u8()?;
+
+        if c.position() != c.into_inner().len() as u64 {
+            return Err(InvalidAccountData.into());
+        }
+
+        Ok(GovernancePayloadSetQuorum {
+            guardian_set_index,
+            quorum,
+        })
+    }
+}
+
+impl SerializeGovernancePayload for GovernancePayloadSetQuorum {
+    const MODULE: &'static str = "Core";
+    const ACTION: u8 = 6;
+}
+
+impl DeserializeGovernancePayload for GovernancePayloadSetQuorum {
+}
+
+pub struct GovernancePayloadSetEmitterFee {
+    // Emitter whose messages are charged the fee
+    pub emitter: Pubkey,
+
+    // Fee in lamports for every message
+    pub fee: U256,
+
+    // Fee in lamports for every byte of the message payload
+    pub fee_per_byte: U256,
+}
+
+impl SerializePayload for GovernancePayloadSetEmitterFee {
+    fn serialize<W: Write>(&self, v: &mut W) -> std::result::Result<(), SolitaireError> {
+        v.write(&self.emitter.to_bytes())?;
+
+        let mut fee_data = [0u8; 32];
+        self.fee.to_big_endian(&mut fee_data);
+        v.write(&fee_data)?;
+
+        let mut fee_per_byte_data = [0u8; 32];
+        self.fee_per_byte.to_big_endian(&mut fee_per_byte_data);
+        v.write(&fee_per_byte_data)?;
+
+        Ok(())
+    }
+}
+
+impl DeserializePayload for GovernancePayloadSetEmitterFee
+where
+    Self: DeserializeGovernancePayload,
+{
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, SolitaireError> {
+        let mut c = Cursor::new(buf);
+        Self::check_governance_header(&mut c)?;
+
+        let mut emitter = [0u8; 32];
+        c.read_exact(&mut emitter)?;
+
+        let mut fee_data: [u8; 32] = [0; 32];
+        c.read_exact(&mut fee_data)?;
+        let fee = U256::from_big_endian(&fee_data);
+
+        let mut fee_per_byte_data: [u8; 32] = [0; 32];
+        c.read_exact(&mut fee_per_byte_data)?;
+        let fee_per_byte = U256::from_big_endian(&fee_per_byte_data);
+
+        if c.position() != c.into_inner().len() as u64 {
+            return Err(InvalidAccountData.into());
+        }
+
+        Ok(GovernancePayloadSetEmitterFee {
+            emitter: Pubkey::new(&emitter[..]),
+            fee,
+            fee_per_byte,
+        })
+    }
+}
+
+impl SerializeGovernancePayload for GovernancePayloadSetEmitterFee {
+    const MODULE: &'static str = "Core";
+    const ACTION: u8 = 7;
+}
+
+impl DeserializeGovernancePayload for GovernancePayloadSetEmitterFee {
+}
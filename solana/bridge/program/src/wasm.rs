@@ -31,10 +31,16 @@ use crate::{
         PostedVAADerivationData,
     },
     instructions::{
+        close_posted_vaa,
+        close_signature_set,
+        emitter_fee_account,
         hash_vaa,
         post_message,
         post_vaa,
+        set_emitter_fee,
         set_fees,
+        set_posted_vaa_expiry,
+        set_quorum,
         transfer_fees,
         upgrade_contract,
         upgrade_guardian_set,
@@ -43,6 +49,8 @@ use crate::{
     types::{
         ConsistencyLevel,
         GovernancePayloadGuardianSetChange,
+        GovernancePayloadSetEmitterFee,
+        GovernancePayloadSetQuorum,
         GovernancePayloadTransferFees,
         GovernancePayloadUpgrade,
     },
@@ -69,16 +77,19 @@ pub fn post_message_ix(
         "FINALIZED" => ConsistencyLevel::Finalized,
         _ => panic!("invalid consistency level"),
     };
-    let ix = post_message(
-        Pubkey::from_str(program_id.as_str()).unwrap(),
+    let program_id = Pubkey::from_str(program_id.as_str()).unwrap();
+    let emitter = Pubkey::from_str(emitter.as_str()).unwrap();
+    let mut ix = post_message(
+        program_id,
         Pubkey::from_str(payer.as_str()).unwrap(),
-        Pubkey::from_str(emitter.as_str()).unwrap(),
+        emitter,
         Pubkey::from_str(message.as_str()).unwrap(),
         nonce,
         msg,
         consistency_level,
     )
     .unwrap();
+    ix.accounts.push(emitter_fee_account(program_id, emitter));
     return JsValue::from_serde(&ix).unwrap();
 }
 
@@ -154,6 +165,113 @@ pub fn set_fees_ix(program_id: String, payer: String, vaa: Vec<u8>) -> JsValue {
     return JsValue::from_serde(&ix).unwrap();
 }
 
+#[wasm_bindgen]
+pub fn set_posted_vaa_expiry_ix(program_id: String, payer: String, vaa: Vec<u8>) -> JsValue {
+    let program_id = Pubkey::from_str(program_id.as_str()).unwrap();
+    let vaa = VAA::deserialize(vaa.as_slice()).unwrap();
+    let message_key = PostedVAA::<'_, { AccountState::Uninitialized }>::key(
+        &PostedVAADerivationData {
+            payload_hash: hash_vaa(&vaa.clone().into()).to_vec(),
+        },
+        &program_id,
+    );
+    let ix = set_posted_vaa_expiry(
+        program_id,
+        Pubkey::from_str(payer.as_str()).unwrap(),
+        message_key,
+        Pubkey::new(&vaa.emitter_address),
+        vaa.sequence,
+    );
+    return JsValue::from_serde(&ix).unwrap();
+}
+
+#[wasm_bindgen]
+pub fn set_quorum_ix(program_id: String, payer: String, vaa: Vec<u8>) -> JsValue {
+    let program_id = Pubkey::from_str(program_id.as_str()).unwrap();
+    let vaa = VAA::deserialize(vaa.as_slice()).unwrap();
+    let payload = GovernancePayloadSetQuorum::deserialize(&mut vaa.payload.as_slice()).unwrap();
+    let message_key = PostedVAA::<'_, { AccountState::Uninitialized }>::key(
+        &PostedVAADerivationData {
+            payload_hash: hash_vaa(&vaa.clone().into()).to_vec(),
+        },
+        &program_id,
+    );
+    let ix = set_quorum(
+        program_id,
+        Pubkey::from_str(payer.as_str()).unwrap(),
+        message_key,
+        Pubkey::new(&vaa.emitter_address),
+        payload.guardian_set_index,
+        vaa.sequence,
+    );
+    return JsValue::from_serde(&ix).unwrap();
+}
+
+#[wasm_bindgen]
+pub fn set_emitter_fee_ix(program_id: String, payer: String, vaa: Vec<u8>) -> JsValue {
+    let program_id = Pubkey::from_str(program_id.as_str()).unwrap();
+    let vaa = VAA::deserialize(vaa.as_slice()).unwrap();
+    let payload =
+        GovernancePayloadSetEmitterFee::deserialize(&mut vaa.payload.as_slice()).unwrap();
+    let message_key = PostedVAA::<'_, { AccountState::Uninitialized }>::key(
+        &PostedVAADerivationData {
+            payload_hash: hash_vaa(&vaa.clone().into()).to_vec(),
+        },
+        &program_id,
+    );
+    let ix = set_emitter_fee(
+        program_id,
+        Pubkey::from_str(payer.as_str()).unwrap(),
+        message_key,
+        Pubkey::new(&vaa.emitter_address),
+        vaa.sequence,
+        payload.emitter,
+    );
+    return JsValue::from_serde(&ix).unwrap();
+}
+
+#[wasm_bindgen]
+pub fn close_signature_set_ix(
+    program_id: String,
+    payer: String,
+    signature_set: String,
+    vaa: Vec<u8>,
+) -> JsValue {
+    let program_id = Pubkey::from_str(program_id.as_str()).unwrap();
+    let vaa = VAA::deserialize(vaa.as_slice()).unwrap();
+    let message_key = PostedVAA::<'_, { AccountState::Uninitialized }>::key(
+        &PostedVAADerivationData {
+            payload_hash: hash_vaa(&vaa.clone().into()).to_vec(),
+        },
+        &program_id,
+    );
+    let ix = close_signature_set(
+        program_id,
+        Pubkey::from_str(payer.as_str()).unwrap(),
+        Pubkey::from_str(signature_set.as_str()).unwrap(),
+        message_key,
+    );
+    return JsValue::from_serde(&ix).unwrap();
+}
+
+#[wasm_bindgen]
+pub fn close_posted_vaa_ix(program_id: String, payer: String, vaa: Vec<u8>) -> JsValue {
+    let program_id = Pubkey::from_str(program_id.as_str()).unwrap();
+    let vaa = VAA::deserialize(vaa.as_slice()).unwrap();
+    let message_key = PostedVAA::<'_, { AccountState::Uninitialized }>::key(
+        &PostedVAADerivationData {
+            payload_hash: hash_vaa(&vaa.clone().into()).to_vec(),
+        },
+        &program_id,
+    );
+    let ix = close_posted_vaa(
+        program_id,
+        Pubkey::from_str(payer.as_str()).unwrap(),
+        message_key,
+    );
+    return JsValue::from_serde(&ix).unwrap();
+}
+
 #[wasm_bindgen]
 pub fn transfer_fees_ix(program_id: String, payer: String, vaa: Vec<u8>) -> JsValue {
     let program_id = Pubkey::from_str(program_id.as_str()).unwrap();
@@ -244,7 +362,7 @@ pub fn verify_signatures_ix(
     let mut verify_txs: Vec<Vec<Instruction>> = Vec::new();
     for (_tx_index, chunk) in signature_items.chunks(7).enumerate() {
         let mut secp_payload = Vec::new();
-        let mut signature_status = [-1i8; 19];
+        let mut signers = Vec::with_capacity(chunk.len());
 
         let data_offset = 1 + chunk.len() * 11;
         let message_offset = data_offset + chunk.len() * 85;
@@ -269,7 +387,7 @@ pub fn verify_signatures_ix(
                 .write_u16::<LittleEndian>(body_hash.len() as u16)
                 .unwrap();
             secp_payload.write_u8(0).unwrap();
-            signature_status[s.index as usize] = i as i8;
+            signers.push((s.index, i as u8));
         }
 
         // Write signatures and addresses
@@ -288,7 +406,8 @@ pub fn verify_signatures_ix(
         };
 
         let payload = VerifySignaturesData {
-            signers: signature_status,
+            signers,
+            secp_instruction_index: None,
         };
 
         let verify_ix = match verify_signatures(
@@ -66,6 +66,8 @@ use bridge::{
         FeeCollector,
         GuardianSet,
         GuardianSetDerivationData,
+        PostedMessageUnreliable,
+        PostedMessageUnreliableDerivationData,
         PostedVAAData,
         PostedVAADerivationData,
         Sequence,
@@ -81,6 +83,7 @@ use bridge::{
     PostMessageData,
     PostVAAData,
     UninitializedMessage,
+    VerifyAndPostVAAData,
     VerifySignaturesData,
 };
 
@@ -113,6 +116,20 @@ pub fn execute(
     )
 }
 
+/// Simulate a transaction without committing it, returning the log messages it produced.
+pub fn simulate(
+    client: &RpcClient,
+    payer: &Keypair,
+    signers: &[&Keypair],
+    instructions: &[Instruction],
+) -> Result<Vec<String>, ClientError> {
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&payer.pubkey()));
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    transaction.sign(&signers.to_vec(), recent_blockhash);
+    let result = client.simulate_transaction(&transaction)?.value;
+    Ok(result.logs.unwrap_or_default())
+}
+
 mod helpers {
     use super::*;
 
@@ -169,20 +186,23 @@ mod helpers {
         (
             secret_keys
                 .iter()
-                .map(|key| {
-                    let public_key = PublicKey::from_secret_key(&key);
-                    let mut h = sha3::Keccak256::default();
-                    h.write(&public_key.serialize()[1..]).unwrap();
-                    let key: [u8; 32] = h.finalize().into();
-                    let mut address = [0u8; 20];
-                    address.copy_from_slice(&key[12..]);
-                    address
-                })
+                .map(generate_address)
                 .collect(),
             secret_keys,
         )
     }
 
+    /// Derive the Ethereum style address of a guardian key.
+    pub fn generate_address(key: &SecretKey) -> [u8; 20] {
+        let public_key = PublicKey::from_secret_key(key);
+        let mut h = sha3::Keccak256::default();
+        h.write(&public_key.serialize()[1..]).unwrap();
+        let key: [u8; 32] = h.finalize().into();
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&key[12..]);
+        address
+    }
+
     /// Utility function for generating VAA's from message data.
     pub fn generate_vaa(
         emitter: &Keypair,
@@ -291,7 +311,7 @@ mod helpers {
         let message = Keypair::new();
 
         // Capture the resulting message, later functions will need this.
-        let instruction = instructions::post_message(
+        let mut instruction = instructions::post_message(
             *program,
             payer.pubkey(),
             emitter.pubkey(),
@@ -301,6 +321,9 @@ mod helpers {
             ConsistencyLevel::Confirmed,
         )
         .unwrap();
+        instruction
+            .accounts
+            .push(instructions::emitter_fee_account(*program, emitter.pubkey()));
 
         execute(
             client,
@@ -316,6 +339,53 @@ mod helpers {
         Ok(message.pubkey())
     }
 
+    pub fn post_message_unreliable(
+        client: &RpcClient,
+        program: &Pubkey,
+        payer: &Keypair,
+        emitter: &Keypair,
+        nonce: u32,
+        data: Vec<u8>,
+        fee: u64,
+    ) -> Result<Pubkey, ClientError> {
+        // Transfer money into the fee collector as it needs a balance/must exist.
+        let fee_collector = FeeCollector::<'_>::key(None, program);
+
+        // The message account is derived from the emitter and reused for every message.
+        let message = PostedMessageUnreliable::<'_, { AccountState::MaybeInitialized }>::key(
+            &PostedMessageUnreliableDerivationData {
+                emitter_key: &emitter.pubkey(),
+            },
+            program,
+        );
+
+        let mut instruction = instructions::post_message_unreliable(
+            *program,
+            payer.pubkey(),
+            emitter.pubkey(),
+            nonce,
+            data,
+            ConsistencyLevel::Confirmed,
+        )
+        .unwrap();
+        instruction
+            .accounts
+            .push(instructions::emitter_fee_account(*program, emitter.pubkey()));
+
+        execute(
+            client,
+            payer,
+            &[payer, emitter],
+            &[
+                system_instruction::transfer(&payer.pubkey(), &fee_collector, fee),
+                instruction,
+            ],
+            CommitmentConfig::processed(),
+        )?;
+
+        Ok(message)
+    }
+
     pub fn verify_signatures(
         client: &RpcClient,
         program: &Pubkey,
@@ -329,8 +399,7 @@ mod helpers {
         // Push Secp256k1 instructions for each signature we want to verify.
         for (i, key) in secret_keys.iter().enumerate() {
             // Set this signers signature position as present at 0.
-            let mut signers = [-1; 19];
-            signers[i] = 0;
+            let signers = vec![(i as u8, 0)];
 
             execute(
                 client,
@@ -343,7 +412,10 @@ mod helpers {
                         payer.pubkey(),
                         guardian_set_version,
                         signature_set.pubkey(),
-                        VerifySignaturesData { signers },
+                        VerifySignaturesData {
+                            signers,
+                            secp_instruction_index: None,
+                        },
                     )
                     .unwrap(),
                 ],
@@ -353,6 +425,104 @@ mod helpers {
         Ok(signature_set.pubkey())
     }
 
+    /// Verify the first signature with a secp256k1 instruction that does not immediately precede
+    /// `verify_signatures`, returning the signature set.
+    pub fn verify_signatures_at_index(
+        client: &RpcClient,
+        program: &Pubkey,
+        payer: &Keypair,
+        body: [u8; 32],
+        secret_keys: &[SecretKey],
+        guardian_set_version: u32,
+    ) -> Result<Pubkey, ClientError> {
+        let signature_set = Keypair::new();
+        let signers = vec![(0, 0)];
+
+        execute(
+            client,
+            payer,
+            &[payer, &signature_set],
+            &vec![
+                new_secp256k1_instruction(&secret_keys[0], &body),
+                system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 0),
+                instructions::verify_signatures(
+                    *program,
+                    payer.pubkey(),
+                    guardian_set_version,
+                    signature_set.pubkey(),
+                    VerifySignaturesData {
+                        signers,
+                        secp_instruction_index: Some(0),
+                    },
+                )
+                .unwrap(),
+            ],
+            CommitmentConfig::processed(),
+        )?;
+        Ok(signature_set.pubkey())
+    }
+
+    /// Verify all signatures in a single secp256k1 instruction and post the VAA along with them.
+    pub fn verify_and_post_vaa(
+        client: &RpcClient,
+        program: &Pubkey,
+        payer: &Keypair,
+        body: [u8; 32],
+        secret_keys: &[SecretKey],
+        vaa: PostVAAData,
+    ) -> Result<Signature, ClientError> {
+        // Lay out the count, a table of offsets, each signature with its address, then the message.
+        let data_offset = 1 + secret_keys.len() * 11;
+        let message_offset = data_offset + secret_keys.len() * 85;
+        let mut offsets = vec![secret_keys.len() as u8];
+        let mut signatures = Vec::new();
+        let mut signers = Vec::with_capacity(secret_keys.len());
+
+        let hash: [u8; 32] = sha3::Keccak256::digest(&body).into();
+        let message = Secp256k1Message::parse_slice(&hash).unwrap();
+        for (i, key) in secret_keys.iter().enumerate() {
+            let signature_offset = (data_offset + 85 * i) as u16;
+            offsets.write(&signature_offset.to_le_bytes()).unwrap();
+            offsets.push(0);
+            offsets.write(&(signature_offset + 65).to_le_bytes()).unwrap();
+            offsets.push(0);
+            offsets.write(&(message_offset as u16).to_le_bytes()).unwrap();
+            offsets.write(&32u16.to_le_bytes()).unwrap();
+            offsets.push(0);
+
+            let (signature, recovery_id) = secp256k1::sign(&message, key);
+            signatures.write(&signature.serialize()).unwrap();
+            signatures.push(recovery_id.serialize());
+            signatures.write(&generate_address(key)).unwrap();
+            signers.push((i as u8, i as u8));
+        }
+
+        let secp_ix = Instruction {
+            program_id: solana_program::secp256k1_program::id(),
+            data: [offsets, signatures, body.to_vec()].concat(),
+            accounts: vec![],
+        };
+
+        execute(
+            client,
+            payer,
+            &[payer],
+            &[
+                secp_ix,
+                instructions::verify_and_post_vaa(
+                    *program,
+                    payer.pubkey(),
+                    VerifyAndPostVAAData {
+                        vaa,
+                        signers,
+                        secp_instruction_index: Some(0),
+                    },
+                ),
+            ],
+            CommitmentConfig::processed(),
+        )
+    }
+
     pub fn post_vaa(
         client: &RpcClient,
         program: &Pubkey,
@@ -475,4 +645,115 @@ mod helpers {
             CommitmentConfig::processed(),
         )
     }
+
+    pub fn set_posted_vaa_expiry(
+        client: &RpcClient,
+        program: &Pubkey,
+        payer: &Keypair,
+        message: Pubkey,
+        emitter: Pubkey,
+        sequence: u64,
+    ) -> Result<Signature, ClientError> {
+        execute(
+            client,
+            payer,
+            &[payer],
+            &[instructions::set_posted_vaa_expiry(
+                *program,
+                payer.pubkey(),
+                message,
+                emitter,
+                sequence,
+            )],
+            CommitmentConfig::processed(),
+        )
+    }
+
+    pub fn set_quorum(
+        client: &RpcClient,
+        program: &Pubkey,
+        payer: &Keypair,
+        message: Pubkey,
+        emitter: Pubkey,
+        guardian_set_index: u32,
+        sequence: u64,
+    ) -> Result<Signature, ClientError> {
+        execute(
+            client,
+            payer,
+            &[payer],
+            &[instructions::set_quorum(
+                *program,
+                payer.pubkey(),
+                message,
+                emitter,
+                guardian_set_index,
+                sequence,
+            )],
+            CommitmentConfig::processed(),
+        )
+    }
+
+    pub fn set_emitter_fee(
+        client: &RpcClient,
+        program: &Pubkey,
+        payer: &Keypair,
+        message: Pubkey,
+        emitter: Pubkey,
+        sequence: u64,
+        fee_emitter: Pubkey,
+    ) -> Result<Signature, ClientError> {
+        execute(
+            client,
+            payer,
+            &[payer],
+            &[instructions::set_emitter_fee(
+                *program,
+                payer.pubkey(),
+                message,
+                emitter,
+                sequence,
+                fee_emitter,
+            )],
+            CommitmentConfig::processed(),
+        )
+    }
+
+    pub fn close_signature_set(
+        client: &RpcClient,
+        program: &Pubkey,
+        payer: &Keypair,
+        refund: Pubkey,
+        signature_set: Pubkey,
+        posted_vaa: Pubkey,
+    ) -> Result<Signature, ClientError> {
+        execute(
+            client,
+            payer,
+            &[payer],
+            &[instructions::close_signature_set(
+                *program,
+                refund,
+                signature_set,
+                posted_vaa,
+            )],
+            CommitmentConfig::processed(),
+        )
+    }
+
+    pub fn close_posted_vaa(
+        client: &RpcClient,
+        program: &Pubkey,
+        payer: &Keypair,
+        refund: Pubkey,
+        posted_vaa: Pubkey,
+    ) -> Result<Signature, ClientError> {
+        execute(
+            client,
+            payer,
+            &[payer],
+            &[instructions::close_posted_vaa(*program, refund, posted_vaa)],
+            CommitmentConfig::processed(),
+        )
+    }
 }
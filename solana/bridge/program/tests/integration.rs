@@ -1,6 +1,9 @@
 #![allow(warnings)]
 
-use borsh::BorshSerialize;
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
 use byteorder::{
     BigEndian,
     WriteBytesExt,
@@ -13,7 +16,10 @@ use secp256k1::{
     SecretKey,
 };
 use sha3::Digest;
-use solana_client::rpc_client::RpcClient;
+use solana_client::{
+    client_error::ClientError,
+    rpc_client::RpcClient,
+};
 use solana_program::{
     borsh::try_from_slice_unchecked,
     hash,
@@ -59,27 +65,44 @@ use bridge::{
         Bridge,
         BridgeConfig,
         BridgeData,
+        EmitterFee,
+        EmitterFeeData,
+        EmitterFeeDerivationData,
         FeeCollector,
         GuardianSet,
         GuardianSetData,
         GuardianSetDerivationData,
         MessageData,
+        PostedMessageUnreliableData,
         PostedVAA,
         PostedVAAData,
         PostedVAADerivationData,
+        Quorum,
+        QuorumData,
+        QuorumDerivationData,
+        RentPayer,
+        RentPayerData,
+        RentPayerDerivationData,
+        Sequence,
+        SequenceDerivationData,
         SequenceTracker,
         SignatureSet,
         SignatureSetData,
     },
+    events,
     instruction,
     instructions::hash_vaa,
     types::{
         ConsistencyLevel,
         GovernancePayloadGuardianSetChange,
+        GovernancePayloadSetEmitterFee,
         GovernancePayloadSetMessageFee,
+        GovernancePayloadSetPostedVAAExpiry,
+        GovernancePayloadSetQuorum,
         GovernancePayloadTransferFees,
         GovernancePayloadUpgrade,
     },
+    Event,
     Initialize,
     PostVAA,
     PostVAAData,
@@ -139,6 +162,9 @@ fn run_integration_tests() {
     // Tests are currently unhygienic as It's difficult to wrap `solana-test-validator` within the
     // integration tests so for now we work around it by simply chain-calling our tests.
     test_bridge_messages(&mut context);
+    test_bridge_messages_unreliable(&mut context);
+    test_verify_signatures_at_index(&mut context);
+    test_verify_and_post_vaa(&mut context);
     test_foreign_bridge_messages(&mut context);
     test_invalid_emitter(&mut context);
     test_duplicate_messages_fail(&mut context);
@@ -151,6 +177,11 @@ fn run_integration_tests() {
     test_transfer_fees_fails(&mut context);
     test_transfer_too_much(&mut context);
     test_transfer_total_fails(&mut context);
+    test_close_accounts(&mut context);
+    test_set_quorum(&mut context);
+    test_large_guardian_set(&mut context);
+    test_emitter_fee(&mut context);
+    test_events(&mut context);
 }
 
 fn test_initialize(context: &mut Context) {
@@ -293,6 +324,119 @@ fn test_bridge_messages(context: &mut Context) {
     }
 }
 
+fn test_bridge_messages_unreliable(context: &mut Context) {
+    let (ref payer, ref client, ref program) = common::setup();
+
+    // Post messages of decreasing size, which reuse the same message account.
+    let emitter = Keypair::new();
+    for size in [32, 16, 0] {
+        let nonce = rand::thread_rng().gen();
+        let sequence = context.seq.next(emitter.pubkey().to_bytes());
+        let message = vec![size as u8; size];
+
+        let message_key = common::post_message_unreliable(
+            client,
+            program,
+            payer,
+            &emitter,
+            nonce,
+            message.clone(),
+            10_000,
+        )
+        .unwrap();
+        common::sync(client, payer);
+
+        // The account keeps the size of the first message, but holds the latest.
+        let account = client.get_account(&message_key).unwrap();
+        let posted_message =
+            PostedMessageUnreliableData::try_from_slice(&account.data).unwrap();
+        assert_eq!(account.data[..3], *b"msu");
+        assert_eq!(posted_message.0.nonce, nonce);
+        assert_eq!(posted_message.0.sequence, sequence);
+        assert_eq!(posted_message.0.payload, message);
+        assert_eq!(
+            posted_message.0.emitter_address,
+            emitter.pubkey().to_bytes()
+        );
+    }
+
+    // A message larger than the account does not fit.
+    let nonce = rand::thread_rng().gen();
+    let message = [0u8; 64].to_vec();
+    assert!(common::post_message_unreliable(
+        client, program, payer, &emitter, nonce, message, 10_000
+    )
+    .is_err());
+    common::sync(client, payer);
+}
+
+fn test_verify_signatures_at_index(context: &mut Context) {
+    let (ref payer, ref client, ref program) = common::setup();
+
+    // The secp256k1 instruction does not need to immediately precede verify_signatures.
+    let emitter = Keypair::new();
+    let nonce = rand::thread_rng().gen();
+    let message = [0u8; 32].to_vec();
+    let (vaa, body, body_hash) = common::generate_vaa(&emitter, message, nonce, 0, 1);
+    let signature_set =
+        common::verify_signatures_at_index(client, program, payer, body, &context.secret, 0)
+            .unwrap();
+    common::sync(client, payer);
+
+    let signatures: SignatureSetData = common::get_account_data(client, &signature_set);
+    assert_eq!(signatures.hash, body);
+    assert_eq!(signatures.signatures[0], true);
+    assert!(signatures.signatures[1..].iter().all(|signature| !signature));
+}
+
+fn test_verify_and_post_vaa(context: &mut Context) {
+    let (ref payer, ref client, ref program) = common::setup();
+
+    let emitter = Keypair::new();
+    let nonce = rand::thread_rng().gen();
+    let message = [0u8; 32].to_vec();
+    let (vaa, body, body_hash) = common::generate_vaa(&emitter, message.clone(), nonce, 0, 1);
+
+    // Too few signatures do not reach quorum.
+    assert!(common::verify_and_post_vaa(
+        client,
+        program,
+        payer,
+        body,
+        &context.secret[..2],
+        vaa.clone(),
+    )
+    .is_err());
+
+    common::verify_and_post_vaa(client, program, payer, body, &context.secret, vaa).unwrap();
+    common::sync(client, payer);
+
+    let posted_vaa = PostedVAA::<'_, { AccountState::Initialized }>::key(
+        &PostedVAADerivationData {
+            payload_hash: body.to_vec(),
+        },
+        program,
+    );
+    let posted_message: PostedVAAData = common::get_account_data(client, &posted_vaa);
+    assert_eq!(posted_message.0.vaa_signature_account, Pubkey::default());
+    assert_eq!(posted_message.0.nonce, nonce);
+    assert_eq!(posted_message.0.payload, message);
+
+    let rent_payer: RentPayerData =
+        common::get_account_data(client, &rent_payer_key(program, &posted_vaa));
+    assert_eq!(rent_payer.payer, payer.pubkey());
+}
+
+/// Key of the RentPayer account recording who paid for a posted VAA or signature set.
+fn rent_payer_key(program: &Pubkey, account: &Pubkey) -> Pubkey {
+    RentPayer::<'_, { AccountState::Initialized }>::key(
+        &RentPayerDerivationData {
+            account_key: account,
+        },
+        program,
+    )
+}
+
 fn test_invalid_emitter(context: &mut Context) {
     let (ref payer, ref client, ref program) = common::setup();
 
@@ -1078,3 +1222,447 @@ fn test_upgrade_contract(context: &mut Context) {
     .unwrap();
     common::sync(client, payer);
 }
+
+fn test_close_accounts(context: &mut Context) {
+    let (ref payer, ref client, ref program) = common::setup();
+
+    // Post a VAA to create the accounts we want to close.
+    let emitter = Keypair::new();
+    let nonce = rand::thread_rng().gen();
+    let message = [0u8; 32].to_vec();
+    common::post_message(client, program, payer, &emitter, nonce, message.clone(), 10_000).unwrap();
+
+    let (vaa, body, body_hash) = common::generate_vaa(&emitter, message.clone(), nonce, 1, 1);
+    let signature_set =
+        common::verify_signatures(client, program, payer, body, &context.secret, 1).unwrap();
+    common::post_vaa(client, program, payer, signature_set, vaa).unwrap();
+    common::sync(client, payer);
+
+    let posted_vaa = PostedVAA::<'_, { AccountState::Initialized }>::key(
+        &PostedVAADerivationData {
+            payload_hash: body.to_vec(),
+        },
+        program,
+    );
+    // The payers are recorded beside the accounts, whose layouts stay as readers expect them.
+    let _: PostedVAAData = common::get_account_data(client, &posted_vaa);
+    let _: SignatureSetData = common::get_account_data(client, &signature_set);
+    let posted_payer: RentPayerData =
+        common::get_account_data(client, &rent_payer_key(program, &posted_vaa));
+    let signatures_payer: RentPayerData =
+        common::get_account_data(client, &rent_payer_key(program, &signature_set));
+    assert_eq!(posted_payer.payer, payer.pubkey());
+    assert_eq!(signatures_payer.payer, payer.pubkey());
+
+    // Only the original payer can be refunded.
+    let other = Keypair::new();
+    assert!(common::close_signature_set(
+        client,
+        program,
+        payer,
+        other.pubkey(),
+        signature_set,
+        posted_vaa,
+    )
+    .is_err());
+
+    let rent = client.get_account(&signature_set).unwrap().lamports;
+    let balance = client.get_account(&payer.pubkey()).unwrap().lamports;
+    common::close_signature_set(
+        client,
+        program,
+        payer,
+        payer.pubkey(),
+        signature_set,
+        posted_vaa,
+    )
+    .unwrap();
+    common::sync(client, payer);
+    assert!(client.get_account(&signature_set).is_err());
+    assert!(client.get_account(&rent_payer_key(program, &signature_set)).is_err());
+    assert!(client.get_account(&payer.pubkey()).unwrap().lamports > balance + rent - 100_000);
+
+    // Posted VAAs never expire until governance sets an expiry.
+    assert!(common::close_posted_vaa(client, program, payer, payer.pubkey(), posted_vaa).is_err());
+
+    let governance = Keypair::from_bytes(&GOVERNANCE_KEY).unwrap();
+    let sequence = context.seq.next(governance.pubkey().to_bytes());
+    let nonce = rand::thread_rng().gen();
+    let message = GovernancePayloadSetPostedVAAExpiry { expiration_time: 1 }
+        .try_to_vec()
+        .unwrap();
+
+    let message_key = common::post_message(
+        client,
+        program,
+        payer,
+        &governance,
+        nonce,
+        message.clone(),
+        10_000,
+    )
+    .unwrap();
+
+    let (vaa, body, body_hash) = common::generate_vaa(&governance, message.clone(), nonce, 1, 1);
+    let governance_signature_set =
+        common::verify_signatures(client, program, payer, body, &context.secret, 1).unwrap();
+    common::post_vaa(client, program, payer, governance_signature_set, vaa).unwrap();
+    common::set_posted_vaa_expiry(
+        client,
+        program,
+        payer,
+        message_key,
+        governance.pubkey(),
+        sequence,
+    )
+    .unwrap();
+
+    // Wait for the posted VAA to expire, after which it can be closed by its payer.
+    std::thread::sleep(Duration::from_secs(3));
+    common::sync(client, payer);
+    assert!(
+        common::close_posted_vaa(client, program, payer, other.pubkey(), posted_vaa).is_err()
+    );
+    common::close_posted_vaa(client, program, payer, payer.pubkey(), posted_vaa).unwrap();
+    common::sync(client, payer);
+    assert!(client.get_account(&posted_vaa).is_err());
+    assert!(client.get_account(&rent_payer_key(program, &posted_vaa)).is_err());
+}
+
+fn test_set_quorum(context: &mut Context) {
+    let (ref payer, ref client, ref program) = common::setup();
+    let guardian_set_key = GuardianSet::<'_, { AccountState::Uninitialized }>::key(
+        &GuardianSetDerivationData { index: 1 },
+        &program,
+    );
+    let quorum_key = Quorum::<'_, { AccountState::Uninitialized }>::key(
+        &QuorumDerivationData {
+            guardian_set_index: 1,
+        },
+        &program,
+    );
+
+    // The quorum cannot exceed the default, and only the active set can be changed.
+    assert!(set_quorum(context, payer, client, program, 1, 2).is_err());
+    assert!(set_quorum(context, payer, client, program, 0, 1).is_err());
+
+    // Setting a quorum creates its account and leaves the guardian set untouched.
+    let guardian_set = client.get_account(&guardian_set_key).unwrap();
+    set_quorum(context, payer, client, program, 1, 1).unwrap();
+    let quorum: QuorumData = common::get_account_data(client, &quorum_key);
+    assert_eq!(quorum.quorum, 1);
+    assert_eq!(client.get_account(&guardian_set_key).unwrap(), guardian_set);
+
+    // Resetting it restores the default quorum.
+    set_quorum(context, payer, client, program, 1, 0).unwrap();
+    let quorum: QuorumData = common::get_account_data(client, &quorum_key);
+    assert_eq!(quorum.quorum, 0);
+}
+
+fn test_large_guardian_set(context: &mut Context) {
+    let (ref payer, ref client, ref program) = common::setup();
+    let emitter = Keypair::from_bytes(&GOVERNANCE_KEY).unwrap();
+
+    // Upgrade to a guardian set larger than the legacy signer array could address.
+    let (new_public_keys, new_secret_keys) = common::generate_keys(25);
+    let nonce = rand::thread_rng().gen();
+    let sequence = context.seq.next(emitter.pubkey().to_bytes());
+    let message = GovernancePayloadGuardianSetChange {
+        new_guardian_set_index: 2,
+        new_guardian_set: new_public_keys.clone(),
+    }
+    .try_to_vec()
+    .unwrap();
+
+    let message_key = common::post_message(
+        client,
+        program,
+        payer,
+        &emitter,
+        nonce,
+        message.clone(),
+        10_000,
+    )
+    .unwrap();
+
+    let (vaa, body, _) = common::generate_vaa(&emitter, message.clone(), nonce, 1, 1);
+    let signature_set =
+        common::verify_signatures(client, program, payer, body, &context.secret, 1).unwrap();
+    common::post_vaa(client, program, payer, signature_set, vaa).unwrap();
+    common::upgrade_guardian_set(
+        client,
+        program,
+        payer,
+        message_key,
+        emitter.pubkey(),
+        1,
+        2,
+        sequence,
+    )
+    .unwrap();
+    common::sync(client, payer);
+
+    let guardian_set_key = GuardianSet::<'_, { AccountState::Uninitialized }>::key(
+        &GuardianSetDerivationData { index: 2 },
+        &program,
+    );
+    let guardian_set: GuardianSetData = common::get_account_data(client, &guardian_set_key);
+    assert_eq!(guardian_set.keys, new_public_keys);
+
+    context.public = new_public_keys;
+    context.secret = new_secret_keys;
+
+    // Every guardian, including those past the legacy limit, can have its signature verified.
+    let message = [7u8; 32].to_vec();
+    let nonce = rand::thread_rng().gen();
+    common::post_message(
+        client,
+        program,
+        payer,
+        &emitter,
+        nonce,
+        message.clone(),
+        10_000,
+    )
+    .unwrap();
+    context.seq.next(emitter.pubkey().to_bytes());
+
+    let (vaa, body, _) = common::generate_vaa(&emitter, message.clone(), nonce, 2, 1);
+    let signature_set =
+        common::verify_signatures(client, program, payer, body, &context.secret, 2).unwrap();
+    common::post_vaa(client, program, payer, signature_set, vaa).unwrap();
+    common::sync(client, payer);
+
+    let signatures: SignatureSetData = common::get_account_data(client, &signature_set);
+    assert_eq!(signatures.guardian_set_index, 2);
+    assert_eq!(signatures.signatures, vec![true; 25]);
+}
+
+fn test_emitter_fee(context: &mut Context) {
+    let (ref payer, ref client, ref program) = common::setup();
+    let emitter = Keypair::new();
+    let fee_collector = FeeCollector::key(None, &program);
+    let emitter_fee_key = EmitterFee::<'_, { AccountState::Uninitialized }>::key(
+        &EmitterFeeDerivationData {
+            emitter_key: &emitter.pubkey(),
+        },
+        &program,
+    );
+    let sequence_key = Sequence::key(
+        &SequenceDerivationData {
+            emitter_key: &emitter.pubkey(),
+        },
+        &program,
+    );
+
+    // Price messages of the emitter by their payload size.
+    set_emitter_fee(context, payer, client, program, emitter.pubkey(), 0, 10);
+    let emitter_fee: EmitterFeeData = common::get_account_data(client, &emitter_fee_key);
+    assert_eq!(emitter_fee.fee, 0);
+    assert_eq!(emitter_fee.fee_per_byte, 10);
+    let sequence: SequenceTracker = common::get_account_data(client, &sequence_key);
+    assert_eq!(sequence.sequence, 0);
+    assert!(sequence.emitter_fee);
+
+    // Posting fails with less than the emitter fee.
+    let account_balance = client.get_account(&fee_collector).unwrap().lamports;
+    let nonce = rand::thread_rng().gen();
+    let message = [0u8; 32].to_vec();
+    assert!(
+        common::post_message(client, program, payer, &emitter, nonce, message.clone(), 319)
+            .is_err()
+    );
+    common::sync(client, payer);
+    assert_eq!(
+        client.get_account(&fee_collector).unwrap().lamports,
+        account_balance,
+    );
+
+    // Or when the EmitterFee account is left out, as legacy clients do.
+    let message_account = Keypair::new();
+    let instruction = bridge::instructions::post_message(
+        *program,
+        payer.pubkey(),
+        emitter.pubkey(),
+        message_account.pubkey(),
+        nonce,
+        message.clone(),
+        ConsistencyLevel::Confirmed,
+    )
+    .unwrap();
+    assert!(common::execute(
+        client,
+        payer,
+        &[payer, &emitter, &message_account],
+        &[
+            system_instruction::transfer(&payer.pubkey(), &fee_collector, 10_000),
+            instruction,
+        ],
+        solana_sdk::commitment_config::CommitmentConfig::processed(),
+    )
+    .is_err());
+    common::sync(client, payer);
+
+    // And succeeds with the emitter fee, keeping the sequence tracker flagged.
+    common::post_message(client, program, payer, &emitter, nonce, message, 320).unwrap();
+    common::sync(client, payer);
+    assert_eq!(
+        client.get_account(&fee_collector).unwrap().lamports,
+        account_balance + 320,
+    );
+    let sequence: SequenceTracker = common::get_account_data(client, &sequence_key);
+    assert_eq!(sequence.sequence, 1);
+    assert!(sequence.emitter_fee);
+
+    // Exempting the emitter lets it post without a fee.
+    set_emitter_fee(context, payer, client, program, emitter.pubkey(), 0, 0);
+    let nonce = rand::thread_rng().gen();
+    common::post_message(client, program, payer, &emitter, nonce, vec![1, 2, 3], 0).unwrap();
+    common::sync(client, payer);
+    let sequence: SequenceTracker = common::get_account_data(client, &sequence_key);
+    assert_eq!(sequence.sequence, 2);
+}
+
+fn test_events(context: &mut Context) {
+    let (ref payer, ref client, ref program) = common::setup();
+    let emitter = Keypair::new();
+    let message = Keypair::new();
+    let nonce = rand::thread_rng().gen();
+    let payload = vec![1, 2, 3];
+
+    let fee_collector = FeeCollector::key(None, &program);
+    let logs = common::simulate(
+        client,
+        payer,
+        &[payer, &emitter, &message],
+        &[
+            system_instruction::transfer(&payer.pubkey(), &fee_collector, 10_000),
+            bridge::instructions::post_message(
+                *program,
+                payer.pubkey(),
+                emitter.pubkey(),
+                message.pubkey(),
+                nonce,
+                payload.clone(),
+                ConsistencyLevel::Finalized,
+            )
+            .unwrap(),
+        ],
+    )
+    .unwrap();
+
+    // Only the message event is decoded, the system program transfer logs no data.
+    let events = events::decode_logs(program, &logs);
+    match &events[..] {
+        [Ok(Event::MessagePosted {
+            message: message_key,
+            emitter: emitter_key,
+            sequence: 0,
+            nonce: event_nonce,
+            consistency_level: 32,
+            payload: event_payload,
+            ..
+        })] => {
+            assert_eq!(*message_key, message.pubkey());
+            assert_eq!(*emitter_key, emitter.pubkey());
+            assert_eq!(*event_nonce, nonce);
+            assert_eq!(*event_payload, payload);
+        }
+        _ => panic!("unexpected events: {:?}", events),
+    }
+}
+
+/// Post and apply a governance quorum change, signed by the current guardian set.
+fn set_quorum(
+    context: &mut Context,
+    payer: &Keypair,
+    client: &RpcClient,
+    program: &Pubkey,
+    guardian_set_index: u32,
+    quorum: u8,
+) -> Result<(), ClientError> {
+    let governance = Keypair::from_bytes(&GOVERNANCE_KEY).unwrap();
+    let sequence = context.seq.next(governance.pubkey().to_bytes());
+    let nonce = rand::thread_rng().gen();
+    let message = GovernancePayloadSetQuorum {
+        guardian_set_index,
+        quorum,
+    }
+    .try_to_vec()
+    .unwrap();
+
+    let message_key = common::post_message(
+        client,
+        program,
+        payer,
+        &governance,
+        nonce,
+        message.clone(),
+        10_000,
+    )
+    .unwrap();
+
+    let (vaa, body, _) = common::generate_vaa(&governance, message, nonce, 1, 1);
+    let signature_set =
+        common::verify_signatures(client, program, payer, body, &context.secret, 1).unwrap();
+    common::post_vaa(client, program, payer, signature_set, vaa).unwrap();
+    let result = common::set_quorum(
+        client,
+        program,
+        payer,
+        message_key,
+        governance.pubkey(),
+        guardian_set_index,
+        sequence,
+    );
+    common::sync(client, payer);
+    result.map(|_| ())
+}
+
+fn set_emitter_fee(
+    context: &mut Context,
+    payer: &Keypair,
+    client: &RpcClient,
+    program: &Pubkey,
+    emitter: Pubkey,
+    fee: u64,
+    fee_per_byte: u64,
+) {
+    let governance = Keypair::from_bytes(&GOVERNANCE_KEY).unwrap();
+    let sequence = context.seq.next(governance.pubkey().to_bytes());
+    let nonce = rand::thread_rng().gen();
+    let message = GovernancePayloadSetEmitterFee {
+        emitter,
+        fee: U256::from(fee),
+        fee_per_byte: U256::from(fee_per_byte),
+    }
+    .try_to_vec()
+    .unwrap();
+
+    let message_key = common::post_message(
+        client,
+        program,
+        payer,
+        &governance,
+        nonce,
+        message.clone(),
+        10_000,
+    )
+    .unwrap();
+
+    let (vaa, body, _) = common::generate_vaa(&governance, message, nonce, 2, 1);
+    let signature_set =
+        common::verify_signatures(client, program, payer, body, &context.secret, 2).unwrap();
+    common::post_vaa(client, program, payer, signature_set, vaa).unwrap();
+    common::set_emitter_fee(
+        client,
+        program,
+        payer,
+        message_key,
+        governance.pubkey(),
+        sequence,
+        emitter,
+    )
+    .unwrap();
+    common::sync(client, payer);
+}
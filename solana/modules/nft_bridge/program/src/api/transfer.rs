@@ -175,14 +175,6 @@ pub fn transfer_native(
     )?;
     invoke_seeded(&transfer_ix, ctx, &accs.authority_signer, None)?;
 
-    // Pay fee
-    let transfer_ix = solana_program::system_instruction::transfer(
-        accs.payer.key,
-        accs.fee_collector.key,
-        accs.bridge.config.fee,
-    );
-    invoke(&transfer_ix, ctx.accounts)?;
-
     let metadata: Metadata =
         Metadata::from_account_info(accs.spl_metadata.info()).ok_or(InvalidMetadata)?;
 
@@ -200,29 +192,50 @@ pub fn transfer_native(
         uri: metadata.data.uri,
         token_id: U256::from_big_endian(&accs.mint.info().key.to_bytes()),
     };
+    let payload = payload.try_to_vec()?;
+
+    // Pay fee
+    let (message_fee, emitter_fee) = cpi_message_fee(
+        ctx.accounts,
+        &accs.config.wormhole_bridge,
+        accs.bridge.config.fee,
+        accs.emitter.key,
+        payload.len(),
+    )?;
+    let transfer_ix = solana_program::system_instruction::transfer(
+        accs.payer.key,
+        accs.fee_collector.key,
+        message_fee,
+    );
+    invoke(&transfer_ix, ctx.accounts)?;
+
     let params = (
         bridge::instruction::Instruction::PostMessage,
         PostMessageData {
             nonce: data.nonce,
-            payload: payload.try_to_vec()?,
+            payload,
             consistency_level: ConsistencyLevel::Finalized,
         },
     );
 
+    let mut accounts = vec![
+        AccountMeta::new(*accs.bridge.info().key, false),
+        AccountMeta::new(*accs.message.key, true),
+        AccountMeta::new_readonly(*accs.emitter.key, true),
+        AccountMeta::new(*accs.sequence.key, false),
+        AccountMeta::new(*accs.payer.key, true),
+        AccountMeta::new(*accs.fee_collector.key, false),
+        AccountMeta::new_readonly(*accs.clock.info().key, false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+    ];
+    // The bridge requires the EmitterFee account once governance has set a fee for the emitter.
+    accounts.extend(emitter_fee);
+
     let ix = Instruction::new_with_bytes(
         accs.config.wormhole_bridge,
         params.try_to_vec()?.as_slice(),
-        vec![
-            AccountMeta::new(*accs.bridge.info().key, false),
-            AccountMeta::new(*accs.message.key, true),
-            AccountMeta::new_readonly(*accs.emitter.key, true),
-            AccountMeta::new(*accs.sequence.key, false),
-            AccountMeta::new(*accs.payer.key, true),
-            AccountMeta::new(*accs.fee_collector.key, false),
-            AccountMeta::new_readonly(*accs.clock.info().key, false),
-            AccountMeta::new_readonly(solana_program::system_program::id(), false),
-            AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
-        ],
+        accounts,
     );
     invoke_seeded(&ix, ctx, &accs.emitter, None)?;
 
@@ -318,15 +331,6 @@ pub fn transfer_wrapped(
     )?;
     invoke_seeded(&burn_ix, ctx, &accs.authority_signer, None)?;
 
-    // Pay fee
-    let transfer_ix = solana_program::system_instruction::transfer(
-        accs.payer.key,
-        accs.fee_collector.key,
-        accs.bridge.config.fee,
-    );
-
-    invoke(&transfer_ix, ctx.accounts)?;
-
     // Enfoce wrapped meta to be uninitialized.
     let derivation_data: WrappedMetaDerivationData = (&*accs).into();
     accs.wrapped_meta
@@ -359,29 +363,50 @@ pub fn transfer_wrapped(
         name: metadata.data.name,
         uri: metadata.data.uri,
     };
+    let payload = payload.try_to_vec()?;
+
+    // Pay fee
+    let (message_fee, emitter_fee) = cpi_message_fee(
+        ctx.accounts,
+        &accs.config.wormhole_bridge,
+        accs.bridge.config.fee,
+        accs.emitter.key,
+        payload.len(),
+    )?;
+    let transfer_ix = solana_program::system_instruction::transfer(
+        accs.payer.key,
+        accs.fee_collector.key,
+        message_fee,
+    );
+    invoke(&transfer_ix, ctx.accounts)?;
+
     let params = (
         bridge::instruction::Instruction::PostMessage,
         PostMessageData {
             nonce: data.nonce,
-            payload: payload.try_to_vec()?,
+            payload,
             consistency_level: ConsistencyLevel::Finalized,
         },
     );
 
+    let mut accounts = vec![
+        AccountMeta::new(*accs.bridge.info().key, false),
+        AccountMeta::new(*accs.message.key, true),
+        AccountMeta::new_readonly(*accs.emitter.key, true),
+        AccountMeta::new(*accs.sequence.key, false),
+        AccountMeta::new(*accs.payer.key, true),
+        AccountMeta::new(*accs.fee_collector.key, false),
+        AccountMeta::new_readonly(*accs.clock.info().key, false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+    ];
+    // The bridge requires the EmitterFee account once governance has set a fee for the emitter.
+    accounts.extend(emitter_fee);
+
     let ix = Instruction::new_with_bytes(
         accs.config.wormhole_bridge,
         params.try_to_vec()?.as_slice(),
-        vec![
-            AccountMeta::new(*accs.bridge.info().key, false),
-            AccountMeta::new(*accs.message.key, true),
-            AccountMeta::new_readonly(*accs.emitter.key, true),
-            AccountMeta::new(*accs.sequence.key, false),
-            AccountMeta::new(*accs.payer.key, true),
-            AccountMeta::new(*accs.fee_collector.key, false),
-            AccountMeta::new_readonly(*accs.clock.info().key, false),
-            AccountMeta::new_readonly(solana_program::system_program::id(), false),
-            AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
-        ],
+        accounts,
     );
     invoke_seeded(&ix, ctx, &accs.emitter, None)?;
 
@@ -382,6 +382,8 @@ pub fn transfer_native(
             // Program
             AccountMeta::new_readonly(bridge_id, false),
             AccountMeta::new_readonly(spl_token::id(), false),
+            // Forwarded to post_message
+            bridge::instructions::emitter_fee_account(bridge_id, emitter_key),
         ],
         data: (crate::instruction::Instruction::TransferNative, data).try_to_vec()?,
     })
@@ -460,6 +462,8 @@ pub fn transfer_wrapped(
             // Program
             AccountMeta::new_readonly(bridge_id, false),
             AccountMeta::new_readonly(spl_token::id(), false),
+            // Forwarded to post_message
+            bridge::instructions::emitter_fee_account(bridge_id, emitter_key),
         ],
         data: (crate::instruction::Instruction::TransferWrapped, data).try_to_vec()?,
     })
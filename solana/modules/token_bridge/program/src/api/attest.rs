@@ -21,6 +21,7 @@ use crate::{
 use bridge::{
     accounts::Bridge,
     api::{
+        cpi_message_fee,
         PostMessage,
         PostMessageData,
     },
@@ -125,15 +126,6 @@ pub fn attest_token(
     accs: &mut AttestToken,
     data: AttestTokenData,
 ) -> Result<()> {
-    // Pay fee
-    let transfer_ix = solana_program::system_instruction::transfer(
-        accs.payer.key,
-        accs.fee_collector.key,
-        accs.bridge.config.fee,
-    );
-
-    invoke(&transfer_ix, ctx.accounts)?;
-
     // Enfoce wrapped meta to be uninitialized.
     let derivation_data: WrappedMetaDerivationData = (&*accs).into();
     accs.wrapped_meta
@@ -164,29 +156,50 @@ pub fn attest_token(
         payload.symbol = metadata.data.symbol.clone();
     }
 
+    let payload = payload.try_to_vec()?;
+
+    // Pay fee
+    let (message_fee, emitter_fee) = cpi_message_fee(
+        ctx.accounts,
+        &accs.config.wormhole_bridge,
+        accs.bridge.config.fee,
+        accs.emitter.key,
+        payload.len(),
+    )?;
+    let transfer_ix = solana_program::system_instruction::transfer(
+        accs.payer.key,
+        accs.fee_collector.key,
+        message_fee,
+    );
+    invoke(&transfer_ix, ctx.accounts)?;
+
     let params = (
         bridge::instruction::Instruction::PostMessage,
         PostMessageData {
             nonce: data.nonce,
-            payload: payload.try_to_vec()?,
+            payload,
             consistency_level: ConsistencyLevel::Finalized,
         },
     );
 
+    let mut accounts = vec![
+        AccountMeta::new(*accs.bridge.info().key, false),
+        AccountMeta::new(*accs.message.key, true),
+        AccountMeta::new_readonly(*accs.emitter.key, true),
+        AccountMeta::new(*accs.sequence.key, false),
+        AccountMeta::new(*accs.payer.key, true),
+        AccountMeta::new(*accs.fee_collector.key, false),
+        AccountMeta::new_readonly(*accs.clock.info().key, false),
+        AccountMeta::new_readonly(solana_program::system_program::id(), false),
+        AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
+    ];
+    // The bridge requires the EmitterFee account once governance has set a fee for the emitter.
+    accounts.extend(emitter_fee);
+
     let ix = Instruction::new_with_bytes(
         accs.config.wormhole_bridge,
         params.try_to_vec()?.as_slice(),
-        vec![
-            AccountMeta::new(*accs.bridge.info().key, false),
-            AccountMeta::new(*accs.message.key, true),
-            AccountMeta::new_readonly(*accs.emitter.key, true),
-            AccountMeta::new(*accs.sequence.key, false),
-            AccountMeta::new(*accs.payer.key, true),
-            AccountMeta::new(*accs.fee_collector.key, false),
-            AccountMeta::new_readonly(*accs.clock.info().key, false),
-            AccountMeta::new_readonly(solana_program::system_program::id(), false),
-            AccountMeta::new_readonly(solana_program::sysvar::rent::ID, false),
-        ],
+        accounts,
     );
     invoke_seeded(&ix, ctx, &accs.emitter, None)?;
 
@@ -72,6 +72,7 @@ fn do_init(
         initial_guardian_set: GuardianSetInfo {
             addresses: guardians.clone(),
             expiration_time: 100,
+            quorum: 0,
         },
         guardian_set_expirity: 50,
         wrapped_asset_code_id: 999,
@@ -49,6 +49,7 @@ use crate::{
         GuardianSetUpgrade,
         ParsedVAA,
         SetFee,
+        SetQuorum,
         TransferFee,
     },
 };
@@ -168,6 +169,7 @@ fn handle_governance_payload(deps: DepsMut, env: Env, data: &Vec<u8>) -> StdResu
         2u8 => vaa_update_guardian_set(deps, env, &gov_packet.payload),
         3u8 => handle_set_fee(deps, env, &gov_packet.payload),
         4u8 => handle_transfer_fee(deps, env, &gov_packet.payload),
+        6u8 => handle_set_quorum(deps, env, &gov_packet.payload),
         _ => ContractError::InvalidVAAAction.std_err(),
     }
 }
@@ -318,6 +320,36 @@ pub fn handle_transfer_fee(deps: DepsMut, _env: Env, data: &Vec<u8>) -> StdResul
     })))
 }
 
+pub fn handle_set_quorum(deps: DepsMut, _env: Env, data: &Vec<u8>) -> StdResult<Response> {
+    /* Payload format
+    0   uint32 guardian_set_index
+    4   uint8 quorum
+    */
+
+    let SetQuorum {
+        guardian_set_index,
+        quorum,
+    } = SetQuorum::deserialize(&data)?;
+
+    // Only the active guardian set can be changed
+    let state = config_read(deps.storage).load()?;
+    if guardian_set_index != state.guardian_set_index {
+        return ContractError::NotCurrentGuardianSet.std_err();
+    }
+
+    let mut guardian_set = guardian_set_get(deps.storage, guardian_set_index)?;
+    if quorum as usize > guardian_set.addresses.len() {
+        return ContractError::InvalidQuorum.std_err();
+    }
+    guardian_set.quorum = quorum;
+    guardian_set_set(deps.storage, guardian_set_index, &guardian_set)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "quorum_change")
+        .add_attribute("guardian_set_index", guardian_set_index.to_string())
+        .add_attribute("quorum", guardian_set.quorum().to_string()))
+}
+
 fn handle_post_message(
     deps: DepsMut,
     env: Env,
@@ -98,6 +98,10 @@ pub enum ContractError {
     /// Registering asset outside of the wormhole
     #[error("RegistrationForbidden")]
     RegistrationForbidden,
+
+    /// Quorum is larger than the guardian set
+    #[error("InvalidQuorum")]
+    InvalidQuorum,
 }
 
 impl ContractError {
@@ -198,10 +198,17 @@ pub struct GuardianSetInfo {
     pub addresses: Vec<GuardianAddress>,
     // List of guardian addresses
     pub expiration_time: u64, // Guardian set expiration time
+    // Signatures required to reach consensus, 0 for the default 2/3+1
+    #[serde(default)]
+    pub quorum: u8,
 }
 
 impl GuardianSetInfo {
     pub fn quorum(&self) -> usize {
+        if self.quorum != 0 {
+            return self.quorum as usize;
+        }
+
         // allow quorum of 0 for testing purposes...
         if self.addresses.len() == 0 {
             return 0;
@@ -345,6 +352,7 @@ impl GuardianSetUpgrade {
         let new_guardian_set = GuardianSetInfo {
             addresses,
             expiration_time: 0,
+            quorum: 0,
         };
 
         return Ok(GuardianSetUpgrade {
@@ -392,6 +400,28 @@ impl TransferFee {
     }
 }
 
+// action 6
+pub struct SetQuorum {
+    pub guardian_set_index: u32,
+    pub quorum: u8,
+}
+
+impl SetQuorum {
+    pub fn deserialize(data: &Vec<u8>) -> StdResult<Self> {
+        let data = data.as_slice();
+        if data.len() != 5 {
+            return ContractError::InvalidVAA.std_err();
+        }
+
+        let guardian_set_index = data.get_u32(0);
+        let quorum = data.get_u8(4);
+        Ok(SetQuorum {
+            guardian_set_index,
+            quorum,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -407,6 +437,7 @@ mod tests {
         GuardianSetInfo {
             addresses,
             expiration_time: 0,
+            quorum: 0,
         }
     }
 
@@ -429,6 +460,18 @@ mod tests {
         assert_eq!(build_guardian_set(100).quorum(), 67);
     }
 
+    #[test]
+    fn quardian_set_custom_quorum() {
+        let mut guardian_set = build_guardian_set(5);
+        guardian_set.quorum = 3;
+        assert_eq!(guardian_set.quorum(), 3);
+
+        let set_quorum = SetQuorum::deserialize(&vec![0, 0, 0, 2, 3]).unwrap();
+        assert_eq!(set_quorum.guardian_set_index, 2);
+        assert_eq!(set_quorum.quorum, 3);
+        assert!(SetQuorum::deserialize(&vec![0, 0, 0, 2]).is_err());
+    }
+
     #[test]
     fn test_deserialize() {
         let x = hex::decode("080000000901007bfa71192f886ab6819fa4862e34b4d178962958d9b2e3d9437338c9e5fde1443b809d2886eaa69e0f0158ea517675d96243c9209c3fe1d94d5b19866654c6980000000b150000000500020001020304000000000000000000000000000000000000000000000000000000000000000000000a0261626364").unwrap();